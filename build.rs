@@ -1,7 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+use substreams_ethereum::Abigen;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     prost_build::Config::new()
         .protoc_arg("--experimental_allow_proto3_optional")
         .compile_protos(&["proto/intentsource.proto"], &["proto/", "src/"])?;
-    
+
+    // Typed event decoders, generated from the ABIs written by extract-abi.js
+    // (ERC20.json is the standard Transfer event and is kept by hand)
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    for (contract, abi_path, module) in [
+        ("IntentSource", "abi/IntentSource.json", "intent_source"),
+        ("Inbox", "abi/Inbox.json", "inbox"),
+        ("Prover", "abi/Prover.json", "prover"),
+        ("ERC20", "abi/ERC20.json", "erc20"),
+    ] {
+        println!("cargo:rerun-if-changed={}", abi_path);
+        Abigen::new(contract, abi_path)?
+            .generate()?
+            .write_to_file(out_dir.join(format!("{}.rs", module)))?;
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,45 +1,50 @@
-const { IntentSourceAbi, InboxAbi } = require('@eco-foundation/routes-ts');
+const fs = require('fs');
+const path = require('path');
+const { IntentSourceAbi, InboxAbi, HyperProverAbi, MetaProverAbi } = require('@eco-foundation/routes-ts');
 const { keccak256 } = require('js-sha3');
 
-// Extract ABIs and compute event signatures
-console.log('IntentSource ABI:');
-console.log(JSON.stringify(IntentSourceAbi, null, 2));
-
-console.log('\n\nInbox ABI:');
-console.log(JSON.stringify(InboxAbi, null, 2));
-
-// Extract event signatures from IntentSource
-const intentEvents = IntentSourceAbi.filter(item => item.type === 'event');
-console.log('\nIntentSource Event Signatures:');
-
-intentEvents.forEach(event => {
-  const signature = `${event.name}(${event.inputs.map(input => input.type).join(',')})`;
-  const hash = keccak256(signature);
-  console.log(`${event.name}: ${signature} -> 0x${hash}`);
+// Canonical ABI type: tuples must be expanded to their component types,
+// otherwise the signature hashes to a topic the contract never emits
+function canonicalType(input) {
+  if (!input.type.startsWith('tuple')) {
+    return input.type;
+  }
+  const suffix = input.type.slice('tuple'.length);
+  return `(${input.components.map(canonicalType).join(',')})${suffix}`;
+}
+
+// Provers share one decoder: the union of the HyperProver and MetaProver
+// events, deduplicated by signature.
+const proverEvents = [...HyperProverAbi, ...MetaProverAbi].filter(
+  (item, index, all) =>
+    item.type === 'event' &&
+    all.findIndex(other =>
+      other.type === 'event' &&
+      other.name === item.name &&
+      other.inputs.map(canonicalType).join(',') === item.inputs.map(canonicalType).join(',')
+    ) === index
+);
+
+// Write the event ABIs consumed by build.rs (substreams-ethereum Abigen).
+// Re-run this after a contract upgrade and rebuild; the Rust decoders are
+// regenerated from these files.
+const contracts = {
+  IntentSource: IntentSourceAbi,
+  Inbox: InboxAbi,
+  Prover: proverEvents,
+};
+
+const abiDir = path.join(__dirname, 'abi');
+fs.mkdirSync(abiDir, { recursive: true });
+
+Object.entries(contracts).forEach(([name, abi]) => {
+  const events = abi.filter(item => item.type === 'event');
+  const file = path.join(abiDir, `${name}.json`);
+  fs.writeFileSync(file, JSON.stringify(events, null, 2) + '\n');
+
+  console.log(`\n${name} Event Signatures (${path.relative(__dirname, file)}):`);
+  events.forEach(event => {
+    const signature = `${event.name}(${event.inputs.map(canonicalType).join(',')})`;
+    console.log(`${event.name}: ${signature} -> 0x${keccak256(signature)}`);
+  });
 });
-
-// Extract event signatures from Inbox
-const inboxEvents = InboxAbi.filter(item => item.type === 'event');
-console.log('\nInbox Event Signatures:');
-
-inboxEvents.forEach(event => {
-  const signature = `${event.name}(${event.inputs.map(input => input.type).join(',')})`;
-  const hash = keccak256(signature);
-  console.log(`${event.name}: ${signature} -> 0x${hash}`);
-});
-
-// Log keccak256 hashes for Solidity events
-console.log('\nKeccak256 Event Hashes (for Rust):');
-console.log('// IntentSource Events');
-intentEvents.forEach(event => {
-  const signature = `${event.name}(${event.inputs.map(input => input.type).join(',')})`;
-  const hash = keccak256(signature);
-  console.log(`const ${event.name.toUpperCase().replace(/-/g, '_')}_EVENT_SIG: [u8; 32] = hex!("${hash}");`);
-});
-
-console.log('\n// Inbox Events');
-inboxEvents.forEach(event => {
-  const signature = `${event.name}(${event.inputs.map(input => input.type).join(',')})`;
-  const hash = keccak256(signature);
-  console.log(`const ${event.name.toUpperCase().replace(/-/g, '_')}_EVENT_SIG: [u8; 32] = hex!("${hash}");`);
-});
\ No newline at end of file
@@ -1,7 +1,130 @@
+const fs = require('fs');
+const path = require('path');
 const { EcoProtocolAddresses } = require('@eco-foundation/routes-ts');
 
-console.log('Base Chain ID 8453 Contract Addresses:');
-console.log('IntentSource:', EcoProtocolAddresses['8453'].IntentSource);
-console.log('Inbox:', EcoProtocolAddresses['8453'].Inbox);
-console.log('HyperProver:', EcoProtocolAddresses['8453'].HyperProver);
-console.log('MetaProver:', EcoProtocolAddresses['8453'].MetaProver);
\ No newline at end of file
+// Substreams network names for the chain ids Eco Routes is deployed on.
+// Chains without a Firehose endpoint are reported and skipped.
+const SUBSTREAMS_NETWORKS = {
+  '1': 'mainnet',
+  '10': 'optimism',
+  '137': 'polygon',
+  '8453': 'base-mainnet',
+  '42161': 'arbitrum-one',
+  '84532': 'base-sepolia',
+  '11155111': 'sepolia',
+  '11155420': 'optimism-sepolia',
+  '421614': 'arbitrum-sepolia',
+};
+
+async function rpc(url, method, params) {
+  const response = await fetch(url, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ jsonrpc: '2.0', id: 1, method, params }),
+  });
+  const body = await response.json();
+  if (body.error) {
+    throw new Error(`${method}: ${body.error.message}`);
+  }
+  return body.result;
+}
+
+// First block at which the contract has code, found by binary search over
+// eth_getCode (needs an archive RPC endpoint)
+async function deploymentBlock(url, address) {
+  let high = parseInt(await rpc(url, 'eth_blockNumber', []), 16);
+  if ((await rpc(url, 'eth_getCode', [address, `0x${high.toString(16)}`])) === '0x') {
+    throw new Error(`${address} has no code`);
+  }
+  let low = 0;
+  while (low < high) {
+    const mid = Math.floor((low + high) / 2);
+    const code = await rpc(url, 'eth_getCode', [address, `0x${mid.toString(16)}`]);
+    if (code === '0x') {
+      low = mid + 1;
+    } else {
+      high = mid;
+    }
+  }
+  return low;
+}
+
+// `address@block`, with the deployment block looked up on the chain's RPC endpoint
+async function contractParam(url, address) {
+  const block = await deploymentBlock(url, address);
+  return { param: `${address}@${block}`, block };
+}
+
+async function main() {
+  console.log('Base Chain ID 8453 Contract Addresses:');
+  console.log('IntentSource:', EcoProtocolAddresses['8453'].IntentSource);
+  console.log('Inbox:', EcoProtocolAddresses['8453'].Inbox);
+  console.log('HyperProver:', EcoProtocolAddresses['8453'].HyperProver);
+  console.log('MetaProver:', EcoProtocolAddresses['8453'].MetaProver);
+
+  // `networks:` section for substreams.yaml. Deployment blocks are looked up
+  // on RPC_URL_<chainId>; initialBlock is the earliest of them. Chains
+  // without an endpoint are left out rather than started from genesis.
+  // Read from disk: the package's exports map may not expose package.json
+  const { version } = JSON.parse(
+    fs.readFileSync(path.join(__dirname, 'node_modules/@eco-foundation/routes-ts/package.json'), 'utf8'),
+  );
+  const lines = [
+    `# Generated by get-addresses.js from @eco-foundation/routes-ts ${version}.`,
+    '# Deployment blocks: first block with code, by eth_getCode binary search',
+    '# on RPC_URL_<chainId>.',
+    'networks:',
+  ];
+  const skipped = [];
+  const missingRpc = [];
+  for (const [chainId, addresses] of Object.entries(EcoProtocolAddresses)) {
+    const network = SUBSTREAMS_NETWORKS[chainId];
+    if (!network) {
+      skipped.push(chainId);
+      continue;
+    }
+    const url = process.env[`RPC_URL_${chainId}`];
+    if (!url) {
+      missingRpc.push(`RPC_URL_${chainId} (${network})`);
+      continue;
+    }
+    const intentSource = await contractParam(url, addresses.IntentSource);
+    const inbox = await contractParam(url, addresses.Inbox);
+    const initialBlock = Math.min(intentSource.block, inbox.block);
+    // Provers aren't listed: every prover that can emit a proof on this
+    // chain is named by an IntentCreated here first, and store_provers
+    // picks it up from there.
+    const params = `chain_id=${chainId}&intentsource=${intentSource.param}&inbox=${inbox.param}`;
+    lines.push(`  ${network}:`);
+    lines.push('    initialBlock:');
+    lines.push(`      map_intent_events: ${initialBlock}`);
+    lines.push(`      map_reverted_events: ${initialBlock}`);
+    lines.push(`      map_prover_events: ${initialBlock}`);
+    lines.push(`      store_provers: ${initialBlock}`);
+    lines.push(`      store_block_timestamp: ${initialBlock}`);
+    lines.push(`      map_expired_intents: ${initialBlock}`);
+    lines.push(`      map_intent_amounts: ${initialBlock}`);
+    lines.push(`      map_tvl: ${initialBlock}`);
+    lines.push('    params:');
+    lines.push(`      map_intent_events: "${params}"`);
+    lines.push(`      map_reverted_events: "${params}"`);
+    lines.push(`      map_prover_events: "${params}"`);
+    lines.push(`      store_provers: "${params}"`);
+    lines.push(`      map_tvl: "${params}"`);
+  }
+
+  console.log('\nsubstreams.yaml networks:\n');
+  console.log(lines.join('\n'));
+  if (skipped.length > 0) {
+    console.log(`\n# no Substreams network mapped for: ${skipped.join(', ')}`);
+  }
+  if (missingRpc.length > 0) {
+    console.error(`\nWARNING: left out networks without an RPC endpoint, set ${missingRpc.join(', ')}`);
+    process.exitCode = 1;
+  }
+}
+
+main().catch(err => {
+  console.error(err);
+  process.exit(1);
+});
@@ -0,0 +1,19 @@
+#[allow(clippy::all, dead_code, unused_imports, unused_variables)]
+pub mod intent_source {
+    include!(concat!(env!("OUT_DIR"), "/intent_source.rs"));
+}
+
+#[allow(clippy::all, dead_code, unused_imports, unused_variables)]
+pub mod inbox {
+    include!(concat!(env!("OUT_DIR"), "/inbox.rs"));
+}
+
+#[allow(clippy::all, dead_code, unused_imports, unused_variables)]
+pub mod prover {
+    include!(concat!(env!("OUT_DIR"), "/prover.rs"));
+}
+
+#[allow(clippy::all, dead_code, unused_imports, unused_variables)]
+pub mod erc20 {
+    include!(concat!(env!("OUT_DIR"), "/erc20.rs"));
+}
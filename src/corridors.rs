@@ -0,0 +1,101 @@
+use crate::big_int;
+use crate::lifecycle::{intent_key, status_name};
+use crate::pb::intentsource::v1::{IntentCreated, IntentEvents, IntentStatus, IntentTransition};
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    DeltaBigInt, DeltaInt64, DeltaProto, Deltas, StoreAddBigInt, StoreAddInt64, StoreGetProto,
+};
+use substreams_entity_change::tables::Tables;
+
+// Corridors are keyed by the chain pair an intent was created for,
+// `corridor:{source_chain_id}:{destination_chain_id}:{field}`. Intents are
+// only ever created on their source chain, so each corridor is written by the
+// run indexing that chain.
+
+// Counters per corridor: `intents` and `prover:{address}` (prover mix) from
+// IntentCreated; `fulfilled`, `proven` and `refunded` from the first
+// transition into that status, with the corridor looked up in store_intents.
+#[substreams::handlers::store]
+fn store_corridor_stats(
+    events: IntentEvents,
+    intent_state: Deltas<DeltaProto<IntentTransition>>,
+    intents: StoreGetProto<IntentCreated>,
+    store: StoreAddInt64,
+) {
+    for event in &events.intent_created {
+        store.add(event.ordinal, corridor_key(event, "intents"), 1);
+        store.add(event.ordinal, corridor_key(event, &format!("prover:{}", event.prover)), 1);
+    }
+
+    for delta in intent_state.deltas.iter() {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+
+        let transition = &delta.new_value;
+        if !matches!(transition.status(), IntentStatus::Fulfilled | IntentStatus::Proven | IntentStatus::Refunded) {
+            continue;
+        }
+        let Some(intent) = intents.get_last(intent_key(&transition.intent_hash)) else {
+            continue;
+        };
+
+        store.add(delta.ordinal, corridor_key(&intent, status_name(transition.status())), 1);
+    }
+}
+
+// Reward volume offered per corridor, `corridor:{source}:{destination}:reward:{token}`,
+// with native value under the `native` token
+#[substreams::handlers::store]
+fn store_corridor_rewards(events: IntentEvents, store: StoreAddBigInt) {
+    for event in &events.intent_created {
+        for reward in &event.reward_tokens {
+            store.add(event.ordinal, corridor_key(event, &format!("reward:{}", reward.token)), big_int(&reward.amount));
+        }
+        let native_value = big_int(&event.native_value);
+        if native_value > BigInt::zero() {
+            store.add(event.ordinal, corridor_key(event, "reward:native"), native_value);
+        }
+    }
+}
+
+fn corridor_key(intent: &IntentCreated, field: &str) -> String {
+    format!("corridor:{}:{}:{}", intent.source_chain_id, intent.destination_chain_id, field)
+}
+
+// `{source_chain_id}_{destination_chain_id}`
+fn corridor_id(key: &str) -> String {
+    format!("{}_{}", key::segment_at(key, 1), key::segment_at(key, 2))
+}
+
+// Entity field for a store key: `intents`, `prover_{address}`, `reward_{token}`, ...
+fn field(key: &str) -> String {
+    key.splitn(4, ':').nth(3).unwrap_or_default().replace(':', "_")
+}
+
+// One `corridors` row per chain pair, created with its first intent. Fields
+// hold the stores' running totals: `intents`, `fulfilled`, `proven`,
+// `refunded`, `prover_{address}` and `reward_{token}`.
+pub fn write_corridors(tables: &mut Tables, stats: &Deltas<DeltaInt64>, rewards: &Deltas<DeltaBigInt>) {
+    for delta in stats.deltas.iter() {
+        let field = field(&delta.key);
+        let row = if field == "intents" && delta.operation == Operation::Create {
+            tables
+                .create_row("corridors", corridor_id(&delta.key))
+                .set("source_chain_id", big_int(key::segment_at(&delta.key, 1)))
+                .set("destination_chain_id", big_int(key::segment_at(&delta.key, 2)))
+        } else {
+            tables.update_row("corridors", corridor_id(&delta.key))
+        };
+        row.set(&field, delta.new_value as u64);
+    }
+
+    for delta in rewards.deltas.iter() {
+        tables
+            .update_row("corridors", corridor_id(&delta.key))
+            .set(&field(&delta.key), delta.new_value.clone());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::big_int;
+use crate::lifecycle::{intent_key, status_name};
+use crate::pb::intentsource::v1::{IntentCreated, IntentEvents, IntentStatus, IntentTransition};
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    DeltaBigInt, DeltaInt64, DeltaProto, Deltas, StoreAddBigInt, StoreAddInt64, StoreGetProto, StoreMaxInt64,
+    StoreMinInt64,
+};
+use substreams_entity_change::tables::Tables;
+
+// Intents per creator that reached each status, `creator:{chain_id}:{address}:{status}`.
+// Fed by the first transition into a status (store_intent_state creates), so
+// repeats such as several partial fundings count once. The creator is looked
+// up in store_intents, so intents created on another chain aren't counted.
+// Cross-chain fulfillments show up on the source chain as `proven`.
+#[substreams::handlers::store]
+fn store_creator_stats(
+    intent_state: Deltas<DeltaProto<IntentTransition>>,
+    intents: StoreGetProto<IntentCreated>,
+    store: StoreAddInt64,
+) {
+    for delta in intent_state.deltas.iter() {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+
+        let transition = &delta.new_value;
+        if !matches!(
+            transition.status(),
+            IntentStatus::Created
+                | IntentStatus::Funded
+                | IntentStatus::Fulfilled
+                | IntentStatus::Proven
+                | IntentStatus::Refunded
+        ) {
+            continue;
+        }
+        let Some(intent) = intents.get_last(intent_key(&transition.intent_hash)) else {
+            continue;
+        };
+
+        store.add(
+            delta.ordinal,
+            creator_key(intent.chain_id, &intent.creator, status_name(transition.status())),
+            1,
+        );
+    }
+}
+
+// Reward value offered by each creator, `creator:{chain_id}:{address}:reward:{token}`,
+// with native value under the `native` token
+#[substreams::handlers::store]
+fn store_creator_rewards(events: IntentEvents, store: StoreAddBigInt) {
+    for event in &events.intent_created {
+        for reward in &event.reward_tokens {
+            store.add(
+                event.ordinal,
+                creator_key(event.chain_id, &event.creator, &format!("reward:{}", reward.token)),
+                big_int(&reward.amount),
+            );
+        }
+        let native_value = big_int(&event.native_value);
+        if native_value > BigInt::zero() {
+            store.add(event.ordinal, creator_key(event.chain_id, &event.creator, "reward:native"), native_value);
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_creator_first_seen(events: IntentEvents, store: StoreMinInt64) {
+    for event in &events.intent_created {
+        store.min(event.ordinal, format!("creator:{}:{}", event.chain_id, event.creator), event.timestamp as i64);
+    }
+}
+
+#[substreams::handlers::store]
+fn store_creator_last_seen(events: IntentEvents, store: StoreMaxInt64) {
+    for event in &events.intent_created {
+        store.max(event.ordinal, format!("creator:{}:{}", event.chain_id, event.creator), event.timestamp as i64);
+    }
+}
+
+fn creator_key(chain_id: u64, creator: &str, field: &str) -> String {
+    format!("creator:{}:{}:{}", chain_id, creator, field)
+}
+
+// `{chain_id}_{address}` row id from any `creator:{chain_id}:{address}...` key
+fn creator_id(key: &str) -> String {
+    format!("{}_{}", key::segment_at(key, 1), key::segment_at(key, 2))
+}
+
+// One `creators` row per creator and chain, created with its first intent.
+// Fields hold the stores' running totals: `created`, `funded`, `fulfilled`,
+// `proven`, `refunded`, `reward_{token}`, `first_seen` and `last_seen`
+// (timestamps of the first and latest IntentCreated).
+pub fn write_creators(
+    tables: &mut Tables,
+    first_seen: &Deltas<DeltaInt64>,
+    last_seen: &Deltas<DeltaInt64>,
+    stats: &Deltas<DeltaInt64>,
+    rewards: &Deltas<DeltaBigInt>,
+) {
+    for delta in first_seen.deltas.iter() {
+        let row = if delta.operation == Operation::Create {
+            let chain_id: u64 = key::segment_at(&delta.key, 1).parse().unwrap_or_default();
+            tables
+                .create_row("creators", creator_id(&delta.key))
+                .set("chain_id", chain_id)
+                .set("creator", key::segment_at(&delta.key, 2))
+        } else {
+            tables.update_row("creators", creator_id(&delta.key))
+        };
+        row.set("first_seen", delta.new_value as u64);
+    }
+
+    for delta in last_seen.deltas.iter() {
+        tables.update_row("creators", creator_id(&delta.key)).set("last_seen", delta.new_value as u64);
+    }
+
+    for delta in stats.deltas.iter() {
+        let status = key::last_segment(&delta.key);
+        tables.update_row("creators", creator_id(&delta.key)).set(status, delta.new_value as u64);
+    }
+
+    for delta in rewards.deltas.iter() {
+        let token = key::last_segment(&delta.key);
+        tables
+            .update_row("creators", creator_id(&delta.key))
+            .set(&format!("reward_{}", token), delta.new_value.clone());
+    }
+}
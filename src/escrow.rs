@@ -0,0 +1,226 @@
+use crate::big_int;
+use crate::lifecycle::{intent_key, state_key};
+use crate::pb::intentsource::v1::{
+    EscrowBalance, EscrowBalances, IntentCreated, IntentEvents, IntentStatus, IntentTransition, TokenAmount,
+};
+use std::collections::BTreeMap;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    DeltaInt64, Deltas, StoreAddBigInt, StoreGetBigInt, StoreGetProto, StoreSetIfNotExistsInt64,
+};
+use substreams_entity_change::tables::Tables;
+
+// Reward escrow per intent and token, `escrow:{intent_hash}:{token}`: funded
+// amounts in, Withdrawal and Refund payouts out
+#[substreams::handlers::store]
+fn store_escrow_balances(events: IntentEvents, store: StoreAddBigInt) {
+    for (ordinal, _, intent_hash, amount) in ledger_entries(&events) {
+        store.add(ordinal, escrow_key(intent_hash, &amount.token), amount.signed);
+    }
+}
+
+// Marks escrow keys that have an entry, so the ledger knows which rows are
+// new
+#[substreams::handlers::store]
+fn store_escrow_keys(events: IntentEvents, store: StoreSetIfNotExistsInt64) {
+    for (ordinal, _, intent_hash, amount) in ledger_entries(&events) {
+        store.set_if_not_exists(ordinal, escrow_key(intent_hash, &amount.token), &1);
+    }
+}
+
+// Balance of every (intent, token) the block touched, with its anomalies:
+// `negative` when more was paid out than funded, `residual` when the intent
+// was withdrawn or refunded and something is still left.
+#[substreams::handlers::map]
+fn map_escrow_ledger(
+    events: IntentEvents,
+    escrow_keys: Deltas<DeltaInt64>,
+    balances: StoreGetBigInt,
+    intent_state: StoreGetProto<IntentTransition>,
+    intents: StoreGetProto<IntentCreated>,
+) -> Result<EscrowBalances, substreams::errors::Error> {
+    let mut ledger = EscrowBalances::default();
+
+    // (intent, token) -> (block, timestamp, chain) of the latest event touching it
+    let mut touched = BTreeMap::new();
+    for (intent_hash, tokens, ctx) in touched_tokens(&events) {
+        for token in tokens {
+            touched.insert((intent_hash.to_string(), token), ctx);
+        }
+    }
+
+    // A settlement that paid nothing out still has to be checked against
+    // everything the intent was funded with
+    let settlements = events
+        .withdrawal
+        .iter()
+        .map(|e| (&e.hash, (e.block_number, e.timestamp, e.chain_id)))
+        .chain(events.refund.iter().map(|e| (&e.hash, (e.block_number, e.timestamp, e.chain_id))));
+    for (intent_hash, ctx) in settlements {
+        if let Some(intent) = intents.get_last(intent_key(intent_hash)) {
+            for token in intent.reward_tokens.iter().map(|t| t.token.clone()).chain(["native".to_string()]) {
+                touched.entry((intent_hash.clone(), token)).or_insert(ctx);
+            }
+        }
+    }
+
+    let new_keys: Vec<&str> = escrow_keys
+        .deltas
+        .iter()
+        .filter(|delta| delta.operation == Operation::Create)
+        .map(|delta| delta.key.as_str())
+        .collect();
+
+    for ((intent_hash, token), (block_number, timestamp, chain_id)) in touched {
+        let key = escrow_key(&intent_hash, &token);
+        let Some(balance) = balances.get_last(&key) else {
+            continue;
+        };
+        let settled = [IntentStatus::Withdrawn, IntentStatus::Refunded]
+            .into_iter()
+            .any(|status| intent_state.get_last(state_key(&intent_hash, status)).is_some());
+
+        let (negative, residual) = anomalies(&balance, settled);
+        ledger.balances.push(EscrowBalance {
+            negative,
+            residual,
+            balance: balance.to_string(),
+            settled,
+            first_entry: new_keys.contains(&key.as_str()),
+            intent_hash,
+            token,
+            block_number,
+            timestamp,
+            chain_id,
+        });
+    }
+
+    Ok(ledger)
+}
+
+// (negative, residual) for an escrow balance
+fn anomalies(balance: &BigInt, settled: bool) -> (bool, bool) {
+    (*balance < BigInt::zero(), settled && *balance != BigInt::zero())
+}
+
+pub struct Entry {
+    pub token: String,
+    pub signed: BigInt,
+}
+
+// (ordinal, chain, intent, signed amount) for every ledger movement in the block
+pub fn ledger_entries(events: &IntentEvents) -> Vec<(u64, u64, &str, Entry)> {
+    let credit = |amount: &TokenAmount| Entry { token: amount.token.clone(), signed: big_int(&amount.amount) };
+    let debit = |amount: &TokenAmount| Entry { token: amount.token.clone(), signed: BigInt::zero() - big_int(&amount.amount) };
+
+    let mut entries = Vec::new();
+    for e in &events.intent_funded {
+        entries.extend(e.funded_amounts.iter().map(|a| (e.ordinal, e.chain_id, e.intent_hash.as_str(), credit(a))));
+    }
+    for e in &events.intent_partially_funded {
+        entries.extend(e.funded_amounts.iter().map(|a| (e.ordinal, e.chain_id, e.intent_hash.as_str(), credit(a))));
+    }
+    for e in &events.withdrawal {
+        entries.extend(e.paid_amounts.iter().map(|a| (e.ordinal, e.chain_id, e.hash.as_str(), debit(a))));
+    }
+    for e in &events.refund {
+        entries.extend(e.paid_amounts.iter().map(|a| (e.ordinal, e.chain_id, e.hash.as_str(), debit(a))));
+    }
+    entries
+}
+
+type EventContext = (u64, u64, u64);
+
+fn touched_tokens(events: &IntentEvents) -> Vec<(&str, Vec<String>, EventContext)> {
+    let tokens = |amounts: &[TokenAmount]| amounts.iter().map(|a| a.token.clone()).collect::<Vec<_>>();
+
+    let mut touched = Vec::new();
+    for e in &events.intent_funded {
+        touched.push((e.intent_hash.as_str(), tokens(&e.funded_amounts), (e.block_number, e.timestamp, e.chain_id)));
+    }
+    for e in &events.intent_partially_funded {
+        touched.push((e.intent_hash.as_str(), tokens(&e.funded_amounts), (e.block_number, e.timestamp, e.chain_id)));
+    }
+    for e in &events.withdrawal {
+        touched.push((e.hash.as_str(), tokens(&e.paid_amounts), (e.block_number, e.timestamp, e.chain_id)));
+    }
+    for e in &events.refund {
+        touched.push((e.hash.as_str(), tokens(&e.paid_amounts), (e.block_number, e.timestamp, e.chain_id)));
+    }
+    touched
+}
+
+fn escrow_key(intent_hash: &str, token: &str) -> String {
+    format!("escrow:{}:{}", intent_hash, token)
+}
+
+// One `escrow_balances` row per intent and token, keyed `{intent_hash}_{token}`
+pub fn write_escrow_balances(tables: &mut Tables, ledger: &EscrowBalances) {
+    for entry in &ledger.balances {
+        let id = format!("{}_{}", entry.intent_hash, entry.token);
+        let row = if entry.first_entry {
+            tables
+                .create_row("escrow_balances", &id)
+                .set("chain_id", entry.chain_id)
+                .set("intent_hash", &entry.intent_hash)
+                .set("token", &entry.token)
+        } else {
+            tables.update_row("escrow_balances", &id)
+        };
+
+        row.set("balance", big_int(&entry.balance))
+            .set("negative", entry.negative)
+            .set("settled", entry.settled)
+            .set("residual", entry.residual)
+            .set("updated_block", entry.block_number)
+            .set("updated_timestamp", entry.timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::intentsource::v1::{IntentFunded, Refund, Withdrawal};
+
+    fn amount(token: &str, amount: u64) -> TokenAmount {
+        TokenAmount { token: token.to_string(), amount: amount.to_string() }
+    }
+
+    // Balance per (intent, token) after replaying the ledger entries
+    fn replay(events: &IntentEvents) -> BTreeMap<(String, String), BigInt> {
+        let mut balances: BTreeMap<(String, String), BigInt> = BTreeMap::new();
+        for (_, _, intent_hash, entry) in ledger_entries(events) {
+            let balance = balances.entry((intent_hash.to_string(), entry.token)).or_insert_with(BigInt::zero);
+            *balance = balance.clone() + entry.signed;
+        }
+        balances
+    }
+
+    // 0xa is funded 100 and paid 120 out, 0xb is funded 100 and refunded 60
+    #[test]
+    fn flags_overpaid_and_leftover_escrow() {
+        let events = IntentEvents {
+            intent_funded: vec![
+                IntentFunded { intent_hash: "0xa".to_string(), funded_amounts: vec![amount("0x01", 100)], ..Default::default() },
+                IntentFunded { intent_hash: "0xb".to_string(), funded_amounts: vec![amount("0x01", 100)], ..Default::default() },
+            ],
+            withdrawal: vec![Withdrawal { hash: "0xa".to_string(), paid_amounts: vec![amount("0x01", 120)], ..Default::default() }],
+            refund: vec![Refund { hash: "0xb".to_string(), paid_amounts: vec![amount("0x01", 60)], ..Default::default() }],
+            ..Default::default()
+        };
+        let balances = replay(&events);
+
+        let overpaid = &balances[&("0xa".to_string(), "0x01".to_string())];
+        assert_eq!(overpaid.to_string(), "-20");
+        assert_eq!(anomalies(overpaid, true), (true, true));
+
+        let leftover = &balances[&("0xb".to_string(), "0x01".to_string())];
+        assert_eq!(leftover.to_string(), "40");
+        assert_eq!(anomalies(leftover, true), (false, true));
+        assert_eq!(anomalies(leftover, false), (false, false));
+
+        assert_eq!(anomalies(&BigInt::zero(), true), (false, false));
+    }
+}
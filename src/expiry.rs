@@ -0,0 +1,187 @@
+use crate::lifecycle::state_key;
+use crate::pb::intentsource::v1::{ExpiredIntents, IntentEvents, IntentExpired, IntentStatus, IntentTransition};
+use substreams::prelude::*;
+use substreams::store::{DeltaInt64, Deltas, StoreAppend, StoreGetArray, StoreGetProto, StoreSetInt64};
+use substreams_ethereum::pb::eth::v2 as eth;
+use substreams_entity_change::tables::Tables;
+
+// Deadlines are grouped per minute, so a block only reads the minutes its
+// timestamp range touches instead of every second of it
+const BUCKET_SECONDS: u64 = 60;
+
+// Intents by deadline minute, `deadline:{unix_seconds / 60}` ->
+// `{deadline}:{intent_hash}` entries. Deadlines that don't fit a u64 never
+// expire and are left out.
+#[substreams::handlers::store]
+fn store_deadlines(events: IntentEvents, store: StoreAppend<String>) {
+    for event in &events.intent_created {
+        if let Ok(deadline) = event.deadline.parse::<u64>() {
+            store.append(event.ordinal, deadline_key(deadline / BUCKET_SECONDS), format!("{}:{}", deadline, event.intent_hash));
+        }
+    }
+}
+
+// Timestamp of the latest block under `timestamp`. Its deltas carry the
+// previous block's timestamp as old_value, which bounds the deadlines a block
+// passes.
+#[substreams::handlers::store]
+fn store_block_timestamp(blk: eth::Block, store: StoreSetInt64) {
+    store.set(0, "timestamp", &(blk.timestamp.as_ref().unwrap().seconds));
+}
+
+// Emits IntentExpired for every intent whose deadline falls in
+// (previous block timestamp, block timestamp], or that is created in the
+// block with its deadline already behind it, and that was neither
+// fulfilled, proven nor withdrawn by then
+#[substreams::handlers::map]
+fn map_expired_intents(
+    blk: eth::Block,
+    events: IntentEvents,
+    block_timestamp: Deltas<DeltaInt64>,
+    deadlines: StoreGetArray<String>,
+    intent_state: StoreGetProto<IntentTransition>,
+) -> Result<ExpiredIntents, substreams::errors::Error> {
+    let mut expired = ExpiredIntents::default();
+
+    let Some(delta) = block_timestamp.deltas.first() else {
+        return Ok(expired);
+    };
+    // The first block has no previous timestamp, and nothing was stored
+    // before it to scan
+    let after = (delta.old_value > 0).then_some(delta.old_value as u64);
+    let until = delta.new_value as u64;
+
+    let created = events
+        .intent_created
+        .iter()
+        .filter_map(|e| e.deadline.parse::<u64>().ok().map(|deadline| (deadline, e.intent_hash.clone())));
+    let passed = passed_deadlines(after, until, created, |bucket| {
+        deadlines.get_last(deadline_key(bucket)).unwrap_or_default()
+    });
+
+    for (deadline, intent_hash) in passed {
+        let status = |status: IntentStatus| intent_state.get_last(state_key(&intent_hash, status));
+        let settled = [IntentStatus::Fulfilled, IntentStatus::Proven, IntentStatus::Withdrawn, IntentStatus::Refunded]
+            .into_iter()
+            .any(|s| status(s).is_some());
+        if settled {
+            continue;
+        }
+        let Some(created) = status(IntentStatus::Created) else {
+            continue;
+        };
+
+        expired.expired.push(IntentExpired {
+            // Funds are only left to refund once some were escrowed
+            refund_eligible: status(IntentStatus::Funded).is_some() || status(IntentStatus::PartiallyFunded).is_some(),
+            intent_hash,
+            deadline,
+            block_number: blk.number,
+            timestamp: until,
+            chain_id: created.chain_id,
+        });
+    }
+
+    Ok(expired)
+}
+
+// (deadline, intent) pairs the block passes. Deadlines in (after, until] are
+// read from the minute buckets the range touches. Intents created in the
+// block with a deadline at or before `after` sit in buckets no later block
+// will read, so they are taken from the block's own IntentCreated events.
+fn passed_deadlines(
+    after: Option<u64>,
+    until: u64,
+    created: impl Iterator<Item = (u64, String)>,
+    bucket: impl Fn(u64) -> Vec<String>,
+) -> Vec<(u64, String)> {
+    let mut passed = Vec::new();
+
+    if let Some(after) = after {
+        for minute in (after + 1) / BUCKET_SECONDS..=until / BUCKET_SECONDS {
+            for entry in bucket(minute) {
+                let Some((deadline, intent_hash)) = entry.split_once(':') else {
+                    continue;
+                };
+                let Ok(deadline) = deadline.parse::<u64>() else {
+                    continue;
+                };
+                if deadline > after && deadline <= until {
+                    passed.push((deadline, intent_hash.to_string()));
+                }
+            }
+        }
+    }
+
+    for (deadline, intent_hash) in created {
+        let scanned = after.is_some_and(|after| deadline > after);
+        if deadline <= until && !scanned {
+            passed.push((deadline, intent_hash));
+        }
+    }
+
+    passed
+}
+
+fn deadline_key(bucket: u64) -> String {
+    format!("deadline:{}", bucket)
+}
+
+// Marks expired intents on their `intents` row
+pub fn write_expired(tables: &mut Tables, expired: &ExpiredIntents) {
+    for intent in &expired.expired {
+        tables
+            .update_row("intents", &intent.intent_hash)
+            .set("expired", true)
+            .set("deadline", intent.deadline)
+            .set("expired_block", intent.block_number)
+            .set("expired_timestamp", intent.timestamp)
+            .set("refund_eligible", intent.refund_eligible);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn buckets(deadlines: &[(u64, &str)]) -> BTreeMap<u64, Vec<String>> {
+        let mut buckets: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+        for (deadline, intent_hash) in deadlines {
+            buckets.entry(deadline / BUCKET_SECONDS).or_default().push(format!("{}:{}", deadline, intent_hash));
+        }
+        buckets
+    }
+
+    #[test]
+    fn reads_deadlines_between_the_block_timestamps() {
+        let stored = buckets(&[(119, "0xa"), (120, "0xb"), (185, "0xc"), (186, "0xd")]);
+        let passed = passed_deadlines(Some(119), 185, std::iter::empty(), |minute| {
+            stored.get(&minute).cloned().unwrap_or_default()
+        });
+
+        assert_eq!(passed, vec![(120, "0xb".to_string()), (185, "0xc".to_string())]);
+    }
+
+    // An intent created at 185 with a deadline of 100 is expired on creation.
+    // One with a deadline in (after, until] is already in the scanned buckets
+    // and must not be reported twice.
+    #[test]
+    fn expires_intents_created_past_their_deadline() {
+        let stored = buckets(&[(100, "0xa"), (150, "0xb"), (300, "0xc")]);
+        let created = [(100, "0xa".to_string()), (150, "0xb".to_string()), (300, "0xc".to_string())];
+        let passed = passed_deadlines(Some(119), 185, created.into_iter(), |minute| {
+            stored.get(&minute).cloned().unwrap_or_default()
+        });
+
+        assert_eq!(passed, vec![(150, "0xb".to_string()), (100, "0xa".to_string())]);
+    }
+
+    #[test]
+    fn first_block_only_checks_its_own_intents() {
+        let created = [(100, "0xa".to_string()), (300, "0xb".to_string())];
+        let passed = passed_deadlines(None, 185, created.into_iter(), |_| panic!("nothing to scan before the first block"));
+
+        assert_eq!(passed, vec![(100, "0xa".to_string())]);
+    }
+}
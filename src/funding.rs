@@ -0,0 +1,400 @@
+use crate::abi::erc20;
+use crate::lifecycle::intent_key;
+use crate::pb::intentsource::v1::{IntentCreated, IntentEvents, TokenAmount};
+use crate::vaults::{self, intent_vault_key};
+use crate::{big_int, hex_string};
+use std::collections::BTreeMap;
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{DeltaBigInt, Deltas, StoreAddBigInt, StoreGetProto, StoreGetString};
+use substreams_ethereum::pb::eth::v2 as eth;
+use substreams_ethereum::Event;
+use substreams_entity_change::tables::Tables;
+
+// map_intent_events with the value each funding and payout moved attached,
+// read from the transaction itself. Movements are matched against the
+// intent's vault from store_intent_vaults; without a known vault nothing is
+// attached. A funding only gets the movements between the previous funding
+// event in the transaction and its own, so repeated fundings of the same
+// intent don't double count.
+#[substreams::handlers::map]
+fn map_intent_amounts(
+    blk: eth::Block,
+    events: IntentEvents,
+    vaults: StoreGetString,
+) -> Result<IntentEvents, substreams::errors::Error> {
+    let mut events = events;
+    attach_funded_amounts(&blk, &mut events, &vaults);
+    attach_paid_amounts(&blk, &mut events, &vaults);
+    vaults::check_settled_vaults(&blk, &mut events, &vaults);
+    Ok(events)
+}
+
+// Funding: ERC-20 Transfers and native value sent to the intent's vault
+fn attach_funded_amounts(blk: &eth::Block, events: &mut IntentEvents, vaults: &StoreGetString) {
+    let windows = Windows::new(
+        events
+            .intent_funded
+            .iter()
+            .map(|e| (e.tx_index, e.ordinal))
+            .chain(events.intent_partially_funded.iter().map(|e| (e.tx_index, e.ordinal))),
+    );
+
+    for event in events.intent_funded.iter_mut() {
+        let Some(vault) = vaults.get_last(intent_vault_key(&event.intent_hash)) else {
+            continue;
+        };
+        if let Some(trx) = transaction(blk, event.tx_index) {
+            event.funded_amounts = funded_in(trx, &vault, windows.start(event.tx_index, event.ordinal), event.ordinal);
+        }
+        event.vault = vault;
+    }
+    for event in events.intent_partially_funded.iter_mut() {
+        let Some(vault) = vaults.get_last(intent_vault_key(&event.intent_hash)) else {
+            continue;
+        };
+        if let Some(trx) = transaction(blk, event.tx_index) {
+            event.funded_amounts = funded_in(trx, &vault, windows.start(event.tx_index, event.ordinal), event.ordinal);
+        }
+        event.vault = vault;
+    }
+}
+
+// Payouts: ERC-20 Transfers and native value sent out of the intent's vault.
+// A vault is only settled once, and it pays out when the settlement deploys
+// it, which can come after the Withdrawal or Refund log, so the whole
+// transaction is searched.
+fn attach_paid_amounts(blk: &eth::Block, events: &mut IntentEvents, vaults: &StoreGetString) {
+    for event in events.withdrawal.iter_mut() {
+        let Some(vault) = vaults.get_last(intent_vault_key(&event.hash)) else {
+            continue;
+        };
+        if let Some(trx) = transaction(blk, event.tx_index) {
+            event.paid_amounts = paid_in(trx, &vault);
+        }
+    }
+    for event in events.refund.iter_mut() {
+        let Some(vault) = vaults.get_last(intent_vault_key(&event.hash)) else {
+            continue;
+        };
+        if let Some(trx) = transaction(blk, event.tx_index) {
+            event.paid_amounts = paid_in(trx, &vault);
+        }
+    }
+}
+
+// Ordinals of the events of one kind per transaction
+struct Windows(BTreeMap<u32, Vec<u64>>);
+
+impl Windows {
+    fn new(events: impl Iterator<Item = (u32, u64)>) -> Self {
+        let mut ordinals: BTreeMap<u32, Vec<u64>> = BTreeMap::new();
+        for (tx_index, ordinal) in events {
+            ordinals.entry(tx_index).or_default().push(ordinal);
+        }
+        Windows(ordinals)
+    }
+
+    // Ordinal of the previous event in the transaction, 0 for the first one
+    fn start(&self, tx_index: u32, ordinal: u64) -> u64 {
+        self.0
+            .get(&tx_index)
+            .and_then(|ordinals| ordinals.iter().filter(|o| **o < ordinal).max().copied())
+            .unwrap_or(0)
+    }
+}
+
+fn transaction(blk: &eth::Block, tx_index: u32) -> Option<&eth::TransactionTrace> {
+    blk.transaction_traces.iter().find(|trx| trx.index == tx_index)
+}
+
+// Everything sent to the vault in the window. Swaps, fees or refunds of
+// excess value elsewhere in the transaction never reach it.
+fn funded_in(trx: &eth::TransactionTrace, vault: &str, after: u64, before: u64) -> Vec<TokenAmount> {
+    token_amounts(movements(trx, after, before, |_, to| to == vault, |call| hex_string(&call.address) == vault))
+}
+
+// Everything that left the vault, including native value swept by its
+// SELFDESTRUCT, which doesn't show up as a call
+fn paid_in(trx: &eth::TransactionTrace, vault: &str) -> Vec<TokenAmount> {
+    let mut totals = movements(trx, 0, u64::MAX, |from, _| from == vault, |call| hex_string(&call.caller) == vault);
+
+    let swept = calls(trx)
+        .flat_map(|call| call.balance_changes.iter())
+        .filter(|change| {
+            change.reason == eth::balance_change::Reason::SuicideWithdraw as i32 && hex_string(&change.address) == vault
+        })
+        .fold(BigInt::zero(), |total, change| total + wei(&change.old_value) - wei(&change.new_value));
+    if swept > BigInt::zero() {
+        let total = totals.entry("native".to_string()).or_insert_with(BigInt::zero);
+        *total = total.clone() + swept;
+    }
+
+    token_amounts(totals)
+}
+
+// Calls whose state was kept; events of reverted calls never reach this module
+fn calls(trx: &eth::TransactionTrace) -> impl Iterator<Item = &eth::Call> {
+    let trx_failed = trx.status != eth::TransactionTraceStatus::Succeeded as i32;
+    trx.calls.iter().filter(move |call| !trx_failed && !call.state_reverted)
+}
+
+// Sums ERC-20 Transfers accepted by `transfer(from, to)` and non-zero native
+// value calls accepted by `native`, strictly between the two ordinals
+fn movements(
+    trx: &eth::TransactionTrace,
+    after: u64,
+    before: u64,
+    transfer: impl Fn(&str, &str) -> bool,
+    native: impl Fn(&eth::Call) -> bool,
+) -> BTreeMap<String, BigInt> {
+    let mut totals: BTreeMap<String, BigInt> = BTreeMap::new();
+    let mut credit = |token: String, amount: BigInt| {
+        let total = totals.entry(token).or_insert_with(BigInt::zero);
+        *total = total.clone() + amount;
+    };
+
+    for call in calls(trx) {
+        for log in &call.logs {
+            if log.ordinal <= after || log.ordinal >= before {
+                continue;
+            }
+            if let Some(event) = erc20::events::Transfer::match_and_decode(log) {
+                if transfer(&hex_string(&event.from), &hex_string(&event.to)) {
+                    credit(hex_string(&log.address), event.value);
+                }
+            }
+        }
+
+        let value = wei(&call.value);
+        if call.begin_ordinal > after && call.begin_ordinal < before && value > BigInt::zero() && native(call) {
+            credit("native".to_string(), value);
+        }
+    }
+
+    totals
+}
+
+fn wei(value: &Option<eth::BigInt>) -> BigInt {
+    value
+        .as_ref()
+        .map(|v| BigInt::from_unsigned_bytes_be(&v.bytes))
+        .unwrap_or_else(BigInt::zero)
+}
+
+fn token_amounts(totals: BTreeMap<String, BigInt>) -> Vec<TokenAmount> {
+    totals
+        .into_iter()
+        .map(|(token, amount)| TokenAmount { token, amount: amount.to_string() })
+        .collect()
+}
+
+// Running funded total per intent and token, `funding:{intent_hash}:{token}`
+#[substreams::handlers::store]
+fn store_intent_funding(events: IntentEvents, store: StoreAddBigInt) {
+    let fundings = events
+        .intent_funded
+        .iter()
+        .map(|e| (e.ordinal, &e.intent_hash, &e.funded_amounts))
+        .chain(events.intent_partially_funded.iter().map(|e| (e.ordinal, &e.intent_hash, &e.funded_amounts)));
+
+    for (ordinal, intent_hash, amounts) in fundings {
+        for amount in amounts {
+            store.add(ordinal, format!("funding:{}:{}", intent_hash, amount.token), big_int(&amount.amount));
+        }
+    }
+}
+
+// One `intent_funding` row per intent and token, reconciling what arrived in
+// the vault with what the intent's `reward_tokens` (or `native_value`) ask
+// for. `required` is only known for intents created on the indexed chain.
+pub fn write_intent_funding(tables: &mut Tables, funding: &Deltas<DeltaBigInt>, intents: &StoreGetProto<IntentCreated>) {
+    for delta in funding.deltas.iter() {
+        let intent_hash = key::segment_at(&delta.key, 1);
+        let token = key::segment_at(&delta.key, 2);
+        let id = format!("{}_{}", intent_hash, token);
+
+        let row = if delta.operation == Operation::Create {
+            tables
+                .create_row("intent_funding", &id)
+                .set("intent_hash", intent_hash)
+                .set("token", token)
+        } else {
+            tables.update_row("intent_funding", &id)
+        };
+        row.set("funded", delta.new_value.clone());
+
+        if let Some(required) = intents.get_last(intent_key(intent_hash)).map(|intent| required(&intent, token)) {
+            row.set("required", required.clone())
+                .set("fully_funded", delta.new_value >= required);
+        }
+    }
+}
+
+fn required(intent: &IntentCreated, token: &str) -> BigInt {
+    if token == "native" {
+        return big_int(&intent.native_value);
+    }
+    intent
+        .reward_tokens
+        .iter()
+        .filter(|reward| reward.token == token)
+        .fold(BigInt::zero(), |total, reward| total + big_int(&reward.amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER_TOPIC: [u8; 32] = crate::topic("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+    fn address(byte: u8) -> Vec<u8> {
+        vec![byte; 20]
+    }
+
+    fn word(bytes: &[u8]) -> Vec<u8> {
+        [vec![0u8; 32 - bytes.len()], bytes.to_vec()].concat()
+    }
+
+    fn value(wei: u64) -> Option<eth::BigInt> {
+        Some(eth::BigInt { bytes: wei.to_be_bytes().to_vec() })
+    }
+
+    // ERC-20 Transfer of `amount` of token `token` from `from` to `to`
+    fn transfer(token: u8, from: u8, to: u8, amount: u64, ordinal: u64) -> eth::Log {
+        eth::Log {
+            address: address(token),
+            topics: vec![TRANSFER_TOPIC.to_vec(), word(&address(from)), word(&address(to))],
+            data: word(&amount.to_be_bytes()),
+            ordinal,
+            ..Default::default()
+        }
+    }
+
+    fn trx(calls: Vec<eth::Call>) -> eth::TransactionTrace {
+        eth::TransactionTrace {
+            status: eth::TransactionTraceStatus::Succeeded as i32,
+            calls,
+            ..Default::default()
+        }
+    }
+
+    // Native value of `address` going from `old` to `new` wei
+    fn balance_change(address_byte: u8, old: u64, new: u64, reason: eth::balance_change::Reason) -> eth::BalanceChange {
+        eth::BalanceChange {
+            address: address(address_byte),
+            old_value: value(old),
+            new_value: value(new),
+            reason: reason as i32,
+            ..Default::default()
+        }
+    }
+
+    fn amounts(expected: &[(&str, u64)]) -> Vec<TokenAmount> {
+        expected
+            .iter()
+            .map(|(token, amount)| TokenAmount { token: token.to_string(), amount: amount.to_string() })
+            .collect()
+    }
+
+    #[test]
+    fn windows_start_after_the_previous_funding_in_the_transaction() {
+        let windows = Windows::new([(1, 30), (1, 10), (1, 20), (2, 15)].into_iter());
+
+        assert_eq!(windows.start(1, 10), 0);
+        assert_eq!(windows.start(1, 20), 10);
+        assert_eq!(windows.start(1, 30), 20);
+        assert_eq!(windows.start(2, 15), 0);
+        assert_eq!(windows.start(3, 5), 0);
+    }
+
+    // Two fundings of the same vault in one transaction, at ordinals 10 and
+    // 20: each only gets what arrived since the previous one
+    #[test]
+    fn splits_fundings_in_one_transaction() {
+        let vault = hex_string(&address(0xaa));
+        let trx = trx(vec![
+            eth::Call {
+                logs: vec![
+                    transfer(0x01, 0xee, 0xaa, 100, 5),
+                    transfer(0x01, 0xee, 0xbb, 1_000, 8),
+                    transfer(0x01, 0xee, 0xaa, 50, 12),
+                ],
+                ..Default::default()
+            },
+            eth::Call { address: address(0xaa), value: value(7), begin_ordinal: 15, ..Default::default() },
+            eth::Call { address: address(0xaa), value: value(9), begin_ordinal: 25, ..Default::default() },
+        ]);
+        let windows = Windows::new([(0, 10), (0, 20)].into_iter());
+        let token = hex_string(&address(0x01));
+
+        assert_eq!(funded_in(&trx, &vault, windows.start(0, 10), 10), amounts(&[(&token, 100)]));
+        assert_eq!(funded_in(&trx, &vault, windows.start(0, 20), 20), amounts(&[(&token, 50), ("native", 7)]));
+    }
+
+    // Both ordinal bounds are exclusive, for logs and for calls
+    #[test]
+    fn movements_stay_strictly_between_the_ordinals() {
+        let trx = trx(vec![
+            eth::Call {
+                logs: vec![
+                    transfer(0x01, 0xee, 0xaa, 1, 10),
+                    transfer(0x01, 0xee, 0xaa, 2, 11),
+                    transfer(0x01, 0xee, 0xaa, 4, 19),
+                    transfer(0x01, 0xee, 0xaa, 8, 20),
+                ],
+                ..Default::default()
+            },
+            eth::Call { value: value(16), begin_ordinal: 10, ..Default::default() },
+            eth::Call { value: value(32), begin_ordinal: 11, ..Default::default() },
+            eth::Call { value: value(64), begin_ordinal: 20, ..Default::default() },
+        ]);
+
+        let totals = movements(&trx, 10, 20, |_, _| true, |_| true);
+        assert_eq!(token_amounts(totals), amounts(&[(&hex_string(&address(0x01)), 6), ("native", 32)]));
+    }
+
+    // The vault's SELFDESTRUCT sweeps its native balance without a call
+    // carrying value, so only the balance change shows it
+    #[test]
+    fn pays_out_the_selfdestruct_sweep() {
+        let vault = hex_string(&address(0xaa));
+        let trx = trx(vec![
+            eth::Call {
+                logs: vec![transfer(0x01, 0xaa, 0xcc, 40, 5), transfer(0x01, 0xee, 0xaa, 1_000, 6)],
+                ..Default::default()
+            },
+            eth::Call { caller: address(0xaa), value: value(3), begin_ordinal: 7, ..Default::default() },
+            eth::Call {
+                balance_changes: vec![
+                    balance_change(0xaa, 100, 0, eth::balance_change::Reason::SuicideWithdraw),
+                    balance_change(0xbb, 500, 0, eth::balance_change::Reason::SuicideWithdraw),
+                    balance_change(0xaa, 0, 100, eth::balance_change::Reason::Transfer),
+                ],
+                ..Default::default()
+            },
+        ]);
+
+        assert_eq!(paid_in(&trx, &vault), amounts(&[(&hex_string(&address(0x01)), 40), ("native", 103)]));
+    }
+
+    #[test]
+    fn skips_reverted_payouts() {
+        let vault = hex_string(&address(0xaa));
+        let reverted = eth::Call {
+            logs: vec![transfer(0x01, 0xaa, 0xcc, 40, 5)],
+            balance_changes: vec![balance_change(0xaa, 100, 0, eth::balance_change::Reason::SuicideWithdraw)],
+            state_reverted: true,
+            ..Default::default()
+        };
+        let kept = eth::Call { caller: address(0xaa), value: value(3), begin_ordinal: 7, ..Default::default() };
+
+        let trx_with_revert = trx(vec![reverted, kept.clone()]);
+        assert_eq!(paid_in(&trx_with_revert, &vault), amounts(&[("native", 3)]));
+
+        let failed = eth::TransactionTrace { status: eth::TransactionTraceStatus::Reverted as i32, ..trx(vec![kept]) };
+        assert_eq!(paid_in(&failed, &vault), amounts(&[]));
+    }
+}
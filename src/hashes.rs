@@ -0,0 +1,194 @@
+use crate::abi::intent_source::events::IntentCreated;
+use ethabi::Token;
+use substreams::scalar::BigInt;
+use tiny_keccak::{Hasher, Keccak};
+
+// Hashes the IntentSource derives an intent's identity from:
+//   routeHash  = keccak256(abi.encode(route))
+//   rewardHash = keccak256(abi.encode(reward))
+//   intentHash = keccak256(abi.encodePacked(routeHash, rewardHash))
+// with
+//   Route  { bytes32 salt; uint256 source; uint256 destination; address inbox; TokenAmount[] tokens; Call[] calls }
+//   Reward { address creator; address prover; uint256 deadline; uint256 nativeValue; TokenAmount[] tokens }
+pub struct IntentHashes {
+    pub route_hash: [u8; 32],
+    pub reward_hash: [u8; 32],
+    pub intent_hash: [u8; 32],
+}
+
+pub fn intent_hashes(event: &IntentCreated) -> IntentHashes {
+    let route = Token::Tuple(vec![
+        Token::FixedBytes(event.salt.to_vec()),
+        uint(&event.source),
+        uint(&event.destination),
+        Token::Address(ethabi::Address::from_slice(&event.inbox)),
+        token_amounts(&event.route_tokens),
+        Token::Array(
+            event
+                .calls
+                .iter()
+                .map(|(target, data, value)| {
+                    Token::Tuple(vec![
+                        Token::Address(ethabi::Address::from_slice(target)),
+                        Token::Bytes(data.clone()),
+                        uint(value),
+                    ])
+                })
+                .collect(),
+        ),
+    ]);
+
+    let route_hash = keccak256(&ethabi::encode(&[route]));
+    let reward_hash = keccak256(&ethabi::encode(&[reward(event)]));
+    let intent_hash = keccak256(&[route_hash, reward_hash].concat());
+
+    IntentHashes {
+        route_hash,
+        reward_hash,
+        intent_hash,
+    }
+}
+
+fn reward(event: &IntentCreated) -> Token {
+    Token::Tuple(vec![
+        Token::Address(ethabi::Address::from_slice(&event.creator)),
+        Token::Address(ethabi::Address::from_slice(&event.prover)),
+        uint(&event.deadline),
+        uint(&event.native_value),
+        token_amounts(&event.reward_tokens),
+    ])
+}
+
+fn token_amounts(tokens: &[(Vec<u8>, BigInt)]) -> Token {
+    Token::Array(
+        tokens
+            .iter()
+            .map(|(token, amount)| Token::Tuple(vec![Token::Address(ethabi::Address::from_slice(token)), uint(amount)]))
+            .collect(),
+    )
+}
+
+// Values were decoded from uint256 fields, so they always fit in 32 bytes
+fn uint(value: &BigInt) -> Token {
+    Token::Uint(ethabi::Uint::from_big_endian(&value.to_bytes_be().1))
+}
+
+// Vault the IntentSource escrows an intent's reward in. It is deployed with
+// CREATE2 from the IntentSource, salted with the route hash, and its
+// constructor takes the intent hash and reward, so the init code differs per
+// intent:
+//   initCode = abi.encodePacked(vaultCreationCode, abi.encode(intentHash, reward))
+pub fn vault_address(intent_source: &[u8], event: &IntentCreated, route_hash: &[u8; 32], creation_code: &[u8]) -> [u8; 20] {
+    let constructor_args = ethabi::encode(&[Token::FixedBytes(event.hash.to_vec()), reward(event)]);
+    let init_code_hash = keccak256(&[creation_code, &constructor_args].concat());
+    create2_address(intent_source, route_hash, &init_code_hash)
+}
+
+// Address of a contract deployed with CREATE2:
+//   keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]
+pub fn create2_address(deployer: &[u8], salt: &[u8], init_code_hash: &[u8; 32]) -> [u8; 20] {
+    let hash = keccak256(&[&[0xff], deployer, salt, init_code_hash].concat());
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex32(value: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&hex::decode(value).unwrap());
+        bytes
+    }
+
+    fn intent() -> IntentCreated {
+        let transfer = hex::decode(format!("a9059cbb{}{}{}000f4240", "00".repeat(12), "cc".repeat(20), "00".repeat(28))).unwrap();
+        IntentCreated {
+            hash: hex32("fbff1ee442108ecdb4db748ef7cb576d943aab3b93713ef6fa10fb6ab7373cfa"),
+            salt: [0x11; 32],
+            source: BigInt::from(8453),
+            destination: BigInt::from(10),
+            inbox: hex::decode("04c816032a076df65b411bb3f31c8d569d411ee2").unwrap(),
+            route_tokens: vec![(vec![0xaa; 20], BigInt::from(1_000_000))],
+            calls: vec![(vec![0xbb; 20], transfer, BigInt::from(0))],
+            creator: vec![0xcc; 20],
+            prover: vec![0xdd; 20],
+            deadline: BigInt::from(1_735_689_600u64),
+            native_value: BigInt::from(0),
+            reward_tokens: vec![(vec![0xee; 20], BigInt::from(1_010_000))],
+        }
+    }
+
+    // Reference hashes computed independently of ethabi, from a hand-written
+    // Solidity ABI encoding of the same Route and Reward
+    #[test]
+    fn recomputes_route_reward_and_intent_hashes() {
+        let event = intent();
+        let hashes = intent_hashes(&event);
+        assert_eq!(hashes.route_hash, hex32("07164cc732e592a86005b624e1529d30ae29321769e05c5be5e15ad43bd26d12"));
+        assert_eq!(hashes.reward_hash, hex32("d8481249ede1f5f32d10ffc47c61f0c138e5cb882f516f24e1bb9fe5c254e2cc"));
+        assert_eq!(hashes.intent_hash, event.hash);
+    }
+
+    // Reference address computed the same way as the reference hashes, with
+    // a stand-in creation code: the vault's real bytecode is deployment
+    // specific
+    #[test]
+    fn derives_vault_from_route_hash_and_constructor_args() {
+        let event = intent();
+        let hashes = intent_hashes(&event);
+        let intent_source = hex::decode("2020ae689ed3e017450280cea110d0ef6e640da4").unwrap();
+        let creation_code = hex::decode("6080604052348015600f57600080fd5b").unwrap();
+
+        let vault = vault_address(&intent_source, &event, &hashes.route_hash, &creation_code);
+        assert_eq!(hex::encode(vault), "9f305d4fc1b7c33392954bfdcbbb154bda267b7e");
+    }
+
+    // Examples from EIP-1014
+    #[test]
+    fn derives_create2_addresses() {
+        let cases = [
+            ("0000000000000000000000000000000000000000", "00".repeat(32), "00", "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"),
+            ("deadbeef00000000000000000000000000000000", "00".repeat(32), "00", "b928f69bb1d91cd65274e3c79d8986362984fda3"),
+            (
+                "deadbeef00000000000000000000000000000000",
+                format!("{}feed{}", "00".repeat(12), "00".repeat(18)),
+                "00",
+                "d04116cdd17bebe565eb2422f2497e06cc1c9833",
+            ),
+            ("0000000000000000000000000000000000000000", "00".repeat(32), "deadbeef", "70f2b2914a2a4b783faefb75f459a580616fcb5e"),
+            (
+                "00000000000000000000000000000000deadbeef",
+                format!("{}cafebabe", "00".repeat(28)),
+                "deadbeef",
+                "60f3f640a8508fc6a86d45df051962668e1e8ac7",
+            ),
+            ("0000000000000000000000000000000000000000", "00".repeat(32), "", "e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0"),
+        ];
+
+        for (deployer, salt, init_code, expected) in cases {
+            let init_code_hash = keccak256(&hex::decode(init_code).unwrap());
+            let address = create2_address(&hex::decode(deployer).unwrap(), &hex::decode(salt).unwrap(), &init_code_hash);
+            assert_eq!(hex::encode(address), expected);
+        }
+    }
+
+    #[test]
+    fn encodes_full_width_uint256() {
+        let max: BigInt = "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            .parse()
+            .unwrap();
+        assert_eq!(uint(&max), Token::Uint(ethabi::Uint::MAX));
+        assert_eq!(uint(&BigInt::from(0)), Token::Uint(ethabi::Uint::zero()));
+    }
+}
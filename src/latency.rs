@@ -0,0 +1,305 @@
+use crate::lifecycle::state_key;
+use crate::pb::intentsource::v1::{
+    IntentEvents, IntentLatencies, IntentLatency, IntentStatus, IntentTransition, LatencyPercentile,
+    LatencyPercentiles,
+};
+use std::collections::BTreeMap;
+use substreams::prelude::*;
+use substreams::store::{StoreAddInt64, StoreGetInt64, StoreGetProto};
+use substreams_entity_change::tables::Tables;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Percentiles cover the fills of the last WINDOW_DAYS days, the current one
+// included
+const WINDOW_DAYS: u64 = 7;
+
+// Latencies are bucketed on a log scale with four buckets per power of two
+// (at most 25% wide). Anything from 2^MAX_OCTAVE seconds (~194 days) on lands
+// in the last bucket, which has no upper bound.
+const MAX_OCTAVE: u32 = 24;
+const BUCKETS: u64 = 4 * (MAX_OCTAVE as u64 - 1) + 1;
+
+// Joins fulfillments and withdrawals with the creation of the same intent.
+// Only intents created on the indexed chain are found in store_intent_state:
+// same-chain intents get both stages, cross-chain ones only the withdrawal.
+#[substreams::handlers::map]
+fn map_intent_latency(
+    events: IntentEvents,
+    intent_state: StoreGetProto<IntentTransition>,
+) -> Result<IntentLatencies, substreams::errors::Error> {
+    let mut latencies = IntentLatencies::default();
+    let created = |intent_hash: &str| intent_state.get_last(state_key(intent_hash, IntentStatus::Created));
+
+    for event in &events.fulfillment {
+        if event.source_chain_id != event.chain_id.to_string() {
+            continue;
+        }
+        let Some(creation) = created(&event.hash) else {
+            continue;
+        };
+
+        latencies.latencies.push(IntentLatency {
+            intent_hash: event.hash.clone(),
+            stage: "fulfilled".to_string(),
+            seconds: event.timestamp.saturating_sub(creation.timestamp),
+            created_timestamp: creation.timestamp,
+            timestamp: event.timestamp,
+            solver: event.claimant.clone(),
+            prover: event.prover.clone(),
+            block_number: event.block_number,
+            tx_hash: event.tx_hash.clone(),
+            ordinal: event.ordinal,
+            chain_id: event.chain_id,
+            first_stage: true,
+        });
+    }
+
+    // Cross-chain intents are fulfilled elsewhere, so their withdrawal is the
+    // first stage seen here
+    for event in &events.withdrawal {
+        let Some(creation) = created(&event.hash) else {
+            continue;
+        };
+        let fulfilled_here = intent_state
+            .get_last(state_key(&event.hash, IntentStatus::Fulfilled))
+            .is_some_and(|fulfillment| fulfillment.chain_id == event.chain_id);
+
+        latencies.latencies.push(IntentLatency {
+            intent_hash: event.hash.clone(),
+            stage: "withdrawn".to_string(),
+            seconds: event.timestamp.saturating_sub(creation.timestamp),
+            created_timestamp: creation.timestamp,
+            timestamp: event.timestamp,
+            solver: String::new(),
+            prover: String::new(),
+            block_number: event.block_number,
+            tx_hash: event.tx_hash.clone(),
+            ordinal: event.ordinal,
+            chain_id: event.chain_id,
+            first_stage: !fulfilled_here,
+        });
+    }
+
+    latencies.latencies.sort_by_key(|l| l.ordinal);
+    Ok(latencies)
+}
+
+// Daily fill-latency histograms per solver and prover, keyed
+// `{solver|prover}:{address}:{day}:{bucket}`, plus an all-time fill count
+// under `{solver|prover}:{address}:fills`.
+#[substreams::handlers::store]
+fn store_latency_histogram(latencies: IntentLatencies, store: StoreAddInt64) {
+    for latency in fills(&latencies) {
+        let day = latency.timestamp / SECONDS_PER_DAY;
+        let bucket = bucket(latency.seconds);
+        for (kind, address) in [("solver", &latency.solver), ("prover", &latency.prover)] {
+            store.add(latency.ordinal, bucket_key(kind, address, day, bucket), 1);
+            store.add(latency.ordinal, fills_key(kind, address), 1);
+        }
+    }
+}
+
+#[substreams::handlers::map]
+fn map_latency_percentiles(
+    latencies: IntentLatencies,
+    histogram: StoreGetInt64,
+) -> Result<LatencyPercentiles, substreams::errors::Error> {
+    let mut percentiles = LatencyPercentiles::default();
+
+    // Fills per address in this block, to tell new rows from updates
+    let mut solvers = BTreeMap::new();
+    let mut provers = BTreeMap::new();
+    for latency in fills(&latencies) {
+        *solvers.entry(latency.solver.as_str()).or_insert(0u64) += 1;
+        *provers.entry(latency.prover.as_str()).or_insert(0u64) += 1;
+    }
+
+    let Some(last) = fills(&latencies).last() else {
+        return Ok(percentiles);
+    };
+
+    for (address, block_fills) in solvers {
+        percentiles.solvers.push(percentile(&histogram, "solver", address, block_fills, last));
+    }
+    for (address, block_fills) in provers {
+        percentiles.provers.push(percentile(&histogram, "prover", address, block_fills, last));
+    }
+
+    Ok(percentiles)
+}
+
+fn percentile(
+    histogram: &StoreGetInt64,
+    kind: &str,
+    address: &str,
+    block_fills: u64,
+    latest: &IntentLatency,
+) -> LatencyPercentile {
+    let today = latest.timestamp / SECONDS_PER_DAY;
+    let mut counts = vec![0u64; BUCKETS as usize];
+    for day in today.saturating_sub(WINDOW_DAYS - 1)..=today {
+        for (bucket, count) in counts.iter_mut().enumerate() {
+            let stored = histogram.get_last(bucket_key(kind, address, day, bucket as u64)).unwrap_or(0);
+            *count += stored.max(0) as u64;
+        }
+    }
+
+    let window_fills = counts.iter().sum();
+    let total_fills = histogram.get_last(fills_key(kind, address)).unwrap_or(0).max(0) as u64;
+
+    LatencyPercentile {
+        address: address.to_string(),
+        window_fills,
+        total_fills,
+        p50_seconds: quantile(&counts, window_fills, 50),
+        p90_seconds: quantile(&counts, window_fills, 90),
+        p99_seconds: quantile(&counts, window_fills, 99),
+        first_fill: total_fills == block_fills,
+        block_number: latest.block_number,
+        timestamp: latest.timestamp,
+        chain_id: latest.chain_id,
+    }
+}
+
+fn fills(latencies: &IntentLatencies) -> impl Iterator<Item = &IntentLatency> {
+    latencies.latencies.iter().filter(|l| l.stage == "fulfilled")
+}
+
+fn bucket_key(kind: &str, address: &str, day: u64, bucket: u64) -> String {
+    format!("{}:{}:{}:{}", kind, address, day, bucket)
+}
+
+fn fills_key(kind: &str, address: &str) -> String {
+    format!("{}:{}:fills", kind, address)
+}
+
+// Below 4s every second has its own bucket; above, each power of two is split
+// in four by the two bits after the leading one.
+fn bucket(seconds: u64) -> u64 {
+    if seconds < 4 {
+        return seconds;
+    }
+    let octave = 63 - seconds.leading_zeros();
+    if octave >= MAX_OCTAVE {
+        return BUCKETS - 1;
+    }
+    let quarter = (seconds >> (octave - 2)) & 3;
+    4 * u64::from(octave - 1) + quarter
+}
+
+// Largest latency that falls in the bucket, u64::MAX for the overflow bucket
+fn bucket_upper_bound(bucket: u64) -> u64 {
+    if bucket < 4 {
+        return bucket;
+    }
+    if bucket >= BUCKETS - 1 {
+        return u64::MAX;
+    }
+    let octave = bucket / 4 + 1;
+    let quarter = bucket % 4;
+    ((5 + quarter) << (octave - 2)) - 1
+}
+
+// Upper bound of the bucket holding the q-th percentile fill
+fn quantile(counts: &[u64], total: u64, q: u64) -> u64 {
+    if total == 0 {
+        return 0;
+    }
+    let rank = (total * q).div_ceil(100).max(1);
+    let mut seen = 0;
+    for (bucket, count) in counts.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return bucket_upper_bound(bucket as u64);
+        }
+    }
+    bucket_upper_bound(BUCKETS - 1)
+}
+
+pub fn write_latencies(tables: &mut Tables, latencies: &IntentLatencies) {
+    for latency in &latencies.latencies {
+        let row = if latency.first_stage {
+            tables
+                .create_row("intent_latency", &latency.intent_hash)
+                .set("intent_hash", &latency.intent_hash)
+                .set("chain_id", latency.chain_id)
+                .set("created_timestamp", latency.created_timestamp)
+        } else {
+            tables.update_row("intent_latency", &latency.intent_hash)
+        };
+        if latency.stage == "fulfilled" {
+            row.set("solver", &latency.solver).set("prover", &latency.prover);
+        }
+
+        row.set(&format!("{}_timestamp", latency.stage), latency.timestamp)
+            .set(&format!("{}_seconds", latency.stage), latency.seconds)
+            .set(&format!("{}_tx_hash", latency.stage), &latency.tx_hash);
+    }
+}
+
+pub fn write_percentiles(tables: &mut Tables, percentiles: &LatencyPercentiles) {
+    for (table, rows) in [("solver_latency", &percentiles.solvers), ("prover_latency", &percentiles.provers)] {
+        for percentile in rows {
+            let id = format!("{}_{}", percentile.chain_id, percentile.address);
+            let row = if percentile.first_fill {
+                tables
+                    .create_row(table, &id)
+                    .set("chain_id", percentile.chain_id)
+                    .set("address", &percentile.address)
+            } else {
+                tables.update_row(table, &id)
+            };
+
+            row.set("window_days", WINDOW_DAYS)
+                .set("window_fills", percentile.window_fills)
+                .set("total_fills", percentile.total_fills)
+                .set("p50_seconds", percentile.p50_seconds)
+                .set("p90_seconds", percentile.p90_seconds)
+                .set("p99_seconds", percentile.p99_seconds)
+                .set("updated_block", percentile.block_number)
+                .set("updated_timestamp", percentile.timestamp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_seconds() {
+        for (seconds, expected) in [(0, 0), (3, 3), (4, 4), (7, 7), (8, 8), (9, 8), (10, 9), (15, 11), (16, 12), (100, 22)] {
+            assert_eq!(bucket(seconds), expected, "{} seconds", seconds);
+        }
+        assert_eq!(bucket((1 << MAX_OCTAVE) - 1), BUCKETS - 2);
+        assert_eq!(bucket(1 << MAX_OCTAVE), BUCKETS - 1);
+        assert_eq!(bucket(u64::MAX), BUCKETS - 1);
+    }
+
+    // Every bucket ends right before the next one starts
+    #[test]
+    fn upper_bounds_are_bucket_edges() {
+        for b in 0..BUCKETS - 1 {
+            let upper = bucket_upper_bound(b);
+            assert_eq!(bucket(upper), b, "upper bound of bucket {}", b);
+            assert_eq!(bucket(upper + 1), b + 1, "past the upper bound of bucket {}", b);
+        }
+        assert_eq!(bucket_upper_bound(BUCKETS - 1), u64::MAX);
+    }
+
+    #[test]
+    fn quantiles_of_a_known_distribution() {
+        // 50 fills in 3s, 40 in 10s, 9 in 100s and one past the last octave
+        let mut counts = vec![0u64; BUCKETS as usize];
+        for (seconds, fills) in [(3, 50), (10, 40), (100, 9), (1 << 30, 1)] {
+            counts[bucket(seconds) as usize] += fills;
+        }
+
+        assert_eq!(quantile(&counts, 100, 50), 3);
+        assert_eq!(quantile(&counts, 100, 90), 11);
+        assert_eq!(quantile(&counts, 100, 99), 111);
+        assert_eq!(quantile(&counts, 100, 100), u64::MAX);
+        assert_eq!(quantile(&[0u64; BUCKETS as usize], 0, 50), 0);
+    }
+}
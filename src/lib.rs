@@ -1,395 +1,1027 @@
+mod abi;
+mod corridors;
+mod creators;
+mod escrow;
+mod expiry;
+mod funding;
+mod hashes;
+mod latency;
+mod lifecycle;
+mod params;
 mod pb;
+mod prover;
 mod schema;
+mod solvers;
+mod tvl;
+mod vaults;
+mod volume;
 
+use abi::{inbox, intent_source};
+use params::Params;
 use pb::intentsource::v1::{
     IntentEvents, IntentCreated, IntentFunded, IntentPartiallyFunded, 
     Withdrawal, Refund, IntentProofChallenged, Fulfillment, OrderFilled,
-    TokenAmount, Call
+    ToBeProven, HyperInstantFulfillment, AddToBatch, SolverWhitelistChanged, SolvingIsPublic, MailboxSet,
+    TokenAmount, Call, DecodeFailure, UnknownLog, IntentTransition, ProverEvents,
+    IntentLatencies, LatencyPercentiles, ExpiredIntents, EscrowBalances, TvlSamples
 };
 use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{DeltaBigInt, DeltaInt64, DeltaProto, Deltas, StoreGetProto};
 use substreams_ethereum::pb::eth::v2 as eth;
 use substreams_ethereum::Event;
 use substreams_entity_change::pb::entity::EntityChanges;
 use substreams_entity_change::tables::Tables;
 
-#[allow(unused_imports)]
-use hex_literal::hex;
-
-// IntentSource contract events - computed from ABI
-const INTENT_CREATED_EVENT_SIG: [u8; 32] = hex!("d74850080f412f4e5145eb98178a1606b34e0b34878d7e2321614b83da2d1249");
-const INTENT_FUNDED_EVENT_SIG: [u8; 32] = hex!("2da42efda5225344c30e729dc0eafc2e56292ac9b9b5c2b16e0e74c86ea5921d");
-const INTENT_PARTIALLY_FUNDED_EVENT_SIG: [u8; 32] = hex!("97cf148f008486c490afd3b522e2398d5039247c7fffe81fcae2a8c6ee622103");
-const WITHDRAWAL_EVENT_SIG: [u8; 32] = hex!("6653a45d3871e4110fa55dac0269f9f93a6d9078d402f7153594e50573d7f0cd");
-const REFUND_EVENT_SIG: [u8; 32] = hex!("0ba6f12b978882904e7444c7a8fcadd2d9f692a6a97aa18e5fb44c3bbc580123");
-const INTENT_PROOF_CHALLENGED_EVENT_SIG: [u8; 32] = hex!("69f2194063569059c6cc65d4599038f27aa9590bbb3f008178b6d20c453b9e82");
+// Position of the log being decoded, copied onto every event emitted for it
+struct LogContext {
+    chain_id: u64,
+    block_number: u64,
+    tx_hash: String,
+    tx_index: u32,
+    timestamp: u64,
+}
 
-// Inbox contract events
-const FULFILLMENT_EVENT_SIG: [u8; 32] = hex!("4a817ec64beb8020b3e400f30f3b458110d5765d7a9d1ace4e68754ed2d082de");
-const ORDER_FILLED_EVENT_SIG: [u8; 32] = hex!("0555709e59fb225fcf12cc582a9e5f7fd8eea54c91f3dc500ab9d8c37c507770");
+impl LogContext {
+    fn new(blk: &eth::Block, trx: &eth::TransactionTrace, chain_id: u64) -> Self {
+        LogContext {
+            chain_id,
+            block_number: blk.number,
+            tx_hash: format!("0x{}", hex::encode(&trx.hash)),
+            tx_index: trx.index,
+            timestamp: blk.timestamp.as_ref().unwrap().seconds as u64,
+        }
+    }
+}
 
-const INTENTSOURCE_CONTRACT_ADDRESS: [u8; 20] = hex!("2020ae689ED3e017450280CEA110d0ef6E640Da4");
-const INBOX_CONTRACT_ADDRESS: [u8; 20] = hex!("04c816032A076dF65b411Bb3F31c8d569d411ee2");
+#[substreams::handlers::map]
+fn map_intent_events(params: String, blk: eth::Block) -> Result<IntentEvents, substreams::errors::Error> {
+    let params = Params::parse(&params)?;
+    Ok(extract_events(&blk, &params, false))
+}
 
+// Opt-in companion to map_intent_events: only logs whose state was rolled back
+// (reverted calls or failed transactions), e.g. losing solver fills. Nothing
+// downstream consumes it, run it directly when analyzing solver competition.
+// Rolled back fundings and payouts moved nothing, so no amounts are attached.
 #[substreams::handlers::map]
-fn map_intent_events(blk: eth::Block) -> Result<IntentEvents, substreams::errors::Error> {
+fn map_reverted_events(params: String, blk: eth::Block) -> Result<IntentEvents, substreams::errors::Error> {
+    let params = Params::parse(&params)?;
+    Ok(extract_events(&blk, &params, true))
+}
+
+// Decode the watched contracts' logs, keeping either the logs that made it
+// into chain state or, with `reverted`, only the ones that were rolled back
+fn extract_events(blk: &eth::Block, params: &Params, reverted: bool) -> IntentEvents {
     let mut events = IntentEvents::default();
 
-    for trx in blk.transaction_traces.iter() {
-        for call in trx.calls.iter() {
-            for log in call.logs.iter() {
-                // Check if log is from our target contracts
-                let is_intentsource = log.address == INTENTSOURCE_CONTRACT_ADDRESS;
-                let is_inbox = log.address == INBOX_CONTRACT_ADDRESS;
-                
-                if !is_intentsource && !is_inbox {
-                    continue;
-                }
-
-                if log.topics.is_empty() {
-                    continue;
-                }
-
-                let topic = log.topics[0].as_slice();
-                let block_number = blk.number;
-                let tx_hash = format!("0x{}", hex::encode(&trx.hash));
-                let timestamp = blk.timestamp.as_ref().unwrap().seconds as u64;
-
-                match topic {
-                    // IntentSource events
-                    INTENT_CREATED_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_intent_created_event(log, block_number, &tx_hash, timestamp) {
-                            events.intent_created.push(event);
-                        }
-                    },
-                    INTENT_FUNDED_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_intent_funded_event(log, block_number, &tx_hash, timestamp) {
-                            events.intent_funded.push(event);
-                        }
-                    },
-                    INTENT_PARTIALLY_FUNDED_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_intent_partially_funded_event(log, block_number, &tx_hash, timestamp) {
-                            events.intent_partially_funded.push(event);
-                        }
-                    },
-                    WITHDRAWAL_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_withdrawal_event(log, block_number, &tx_hash, timestamp) {
-                            events.withdrawal.push(event);
-                        }
-                    },
-                    REFUND_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_refund_event(log, block_number, &tx_hash, timestamp) {
-                            events.refund.push(event);
-                        }
-                    },
-                    INTENT_PROOF_CHALLENGED_EVENT_SIG if is_intentsource => {
-                        if let Some(event) = decode_intent_proof_challenged_event(log, block_number, &tx_hash, timestamp) {
-                            events.intent_proof_challenged.push(event);
-                        }
-                    },
-                    // Inbox events
-                    FULFILLMENT_EVENT_SIG if is_inbox => {
-                        if let Some(event) = decode_fulfillment_event(log, block_number, &tx_hash, timestamp) {
-                            events.fulfillment.push(event);
-                        }
-                    },
-                    ORDER_FILLED_EVENT_SIG if is_inbox => {
-                        if let Some(event) = decode_order_filled_event(log, block_number, &tx_hash, timestamp) {
-                            events.order_filled.push(event);
-                        }
-                    },
-                    _ => {},
-                }
-            }
+    for (trx, log) in block_logs(blk, reverted) {
+        // Check if log is from our target contracts
+        let is_intentsource = params.is_intent_source(&log.address, blk.number);
+        let is_inbox = params.is_inbox(&log.address, blk.number);
+
+        if !is_intentsource && !is_inbox {
+            continue;
         }
+
+        let ctx = LogContext::new(blk, trx, params.chain_id);
+        let decoded = if is_intentsource {
+            decode_intent_source_log(log, &ctx, params, &mut events)
+        } else {
+            decode_inbox_log(log, &ctx, &mut events)
+        };
+
+        match decoded {
+            Ok(true) => {},
+            // A watched contract emitted an event we have no ABI entry for
+            Ok(false) => events.unknown_logs.push(unknown_log(log, &ctx)),
+            Err(reason) => events.decode_failures.push(decode_failure(log, &ctx, reason)),
+        }
+    }
+
+    retain_requested_events(&mut events, params);
+    events
+}
+
+// Every log in the block with its transaction. Logs from reverted calls or
+// failed transactions are only returned when `reverted` is set, and then
+// exclusively.
+fn block_logs(blk: &eth::Block, reverted: bool) -> impl Iterator<Item = (&eth::TransactionTrace, &eth::Log)> {
+    blk.transaction_traces.iter().flat_map(move |trx| {
+        let trx_failed = trx.status != eth::TransactionTraceStatus::Succeeded as i32;
+        trx.calls
+            .iter()
+            .filter(move |call| (trx_failed || call.state_reverted) == reverted)
+            .flat_map(move |call| call.logs.iter().map(move |log| (trx, log)))
+    })
+}
+
+fn unknown_log(log: &eth::Log, ctx: &LogContext) -> UnknownLog {
+    UnknownLog {
+        contract: hex_string(&log.address),
+        topics: log.topics.iter().map(|topic| hex_string(topic)).collect(),
+        data: hex_string(&log.data),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }
+}
+
+fn decode_failure(log: &eth::Log, ctx: &LogContext, reason: String) -> DecodeFailure {
+    DecodeFailure {
+        contract: hex_string(&log.address),
+        topic0: log.topics.first().map(|topic| hex_string(topic)).unwrap_or_default(),
+        reason,
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
     }
+}
 
-    Ok(events)
+// Drop the event kinds not selected by the `events` param. Decode failures
+// and unknown logs are diagnostics and always kept.
+fn retain_requested_events(events: &mut IntentEvents, params: &Params) {
+    if !params.emits("intent_created") {
+        events.intent_created.clear();
+    }
+    if !params.emits("intent_funded") {
+        events.intent_funded.clear();
+    }
+    if !params.emits("intent_partially_funded") {
+        events.intent_partially_funded.clear();
+    }
+    if !params.emits("withdrawal") {
+        events.withdrawal.clear();
+    }
+    if !params.emits("refund") {
+        events.refund.clear();
+    }
+    if !params.emits("intent_proof_challenged") {
+        events.intent_proof_challenged.clear();
+    }
+    if !params.emits("fulfillment") {
+        events.fulfillment.clear();
+    }
+    if !params.emits("order_filled") {
+        events.order_filled.clear();
+    }
+    if !params.emits("to_be_proven") {
+        events.to_be_proven.clear();
+    }
+    if !params.emits("hyper_instant_fulfillment") {
+        events.hyper_instant_fulfillment.clear();
+    }
+    if !params.emits("add_to_batch") {
+        events.add_to_batch.clear();
+    }
+    if !params.emits("solver_whitelist_changed") {
+        events.solver_whitelist_changed.clear();
+    }
+    if !params.emits("solving_is_public") {
+        events.solving_is_public.clear();
+    }
+    if !params.emits("mailbox_set") {
+        events.mailbox_set.clear();
+    }
 }
 
 #[substreams::handlers::map]
-fn db_out(events: IntentEvents) -> Result<EntityChanges, substreams::errors::Error> {
+fn db_out(
+    events: IntentEvents,
+    prover_events: ProverEvents,
+    intent_state: Deltas<DeltaProto<IntentTransition>>,
+    intent_status: Deltas<DeltaInt64>,
+    intent_latency: IntentLatencies,
+    latency_percentiles: LatencyPercentiles,
+    solver_first_block: Deltas<DeltaInt64>,
+    solver_last_block: Deltas<DeltaInt64>,
+    solver_stats: Deltas<DeltaInt64>,
+    solver_rewards: Deltas<DeltaBigInt>,
+    creator_first_seen: Deltas<DeltaInt64>,
+    creator_last_seen: Deltas<DeltaInt64>,
+    creator_stats: Deltas<DeltaInt64>,
+    creator_rewards: Deltas<DeltaBigInt>,
+    token_volume: Deltas<DeltaBigInt>,
+    token_intents: Deltas<DeltaInt64>,
+    corridor_stats: Deltas<DeltaInt64>,
+    corridor_rewards: Deltas<DeltaBigInt>,
+    intent_funding: Deltas<DeltaBigInt>,
+    intents: StoreGetProto<IntentCreated>,
+    expired_intents: ExpiredIntents,
+    escrow_ledger: EscrowBalances,
+    tvl_samples: TvlSamples,
+    tvl_daily_high: Deltas<DeltaBigInt>,
+    tvl_daily_low: Deltas<DeltaBigInt>,
+) -> Result<EntityChanges, substreams::errors::Error> {
     let mut tables = Tables::new();
 
     for event in events.intent_created {
         tables
-            .create_row("intent_created", &event.intent_hash)
+            .create_row("intent_created", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("intent_hash", &event.intent_hash)
+            .set("route_hash", &event.route_hash)
+            .set("reward_hash", &event.reward_hash)
+            .set("computed_intent_hash", &event.computed_intent_hash)
+            .set("hash_mismatch", event.hash_mismatch)
+            .set("intent_source", &event.intent_source)
+            .set("vault", &event.vault)
             .set("salt", &event.salt)
-            .set("source_chain_id", event.source_chain_id)
-            .set("destination_chain_id", event.destination_chain_id)
+            .set("source_chain_id", big_int(&event.source_chain_id))
+            .set("destination_chain_id", big_int(&event.destination_chain_id))
             .set("inbox_address", &event.inbox_address)
             .set("creator", &event.creator)
             .set("prover", &event.prover)
-            .set("deadline", event.deadline)
-            .set("native_value", event.native_value)
+            .set("route_tokens", event.route_tokens.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("route_amounts", event.route_tokens.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
+            .set("call_targets", event.calls.iter().map(|c| c.target.clone()).collect::<Vec<_>>())
+            .set("call_data", event.calls.iter().map(|c| c.data.clone()).collect::<Vec<_>>())
+            .set("call_values", event.calls.iter().map(|c| big_int(&c.value)).collect::<Vec<_>>())
+            .set("deadline", big_int(&event.deadline))
+            .set("native_value", big_int(&event.native_value))
+            .set("reward_tokens", event.reward_tokens.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("reward_amounts", event.reward_tokens.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.intent_funded {
         tables
-            .create_row("intent_funded", &format!("{}_{}", event.intent_hash, event.block_number))
+            .create_row("intent_funded", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("intent_hash", &event.intent_hash)
             .set("funder", &event.funder)
+            .set("vault", &event.vault)
+            .set("funded_tokens", event.funded_amounts.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("funded_amounts", event.funded_amounts.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.intent_partially_funded {
         tables
-            .create_row("intent_partially_funded", &format!("{}_{}", event.intent_hash, event.block_number))
+            .create_row("intent_partially_funded", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("intent_hash", &event.intent_hash)
             .set("funder", &event.funder)
+            .set("vault", &event.vault)
+            .set("funded_tokens", event.funded_amounts.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("funded_amounts", event.funded_amounts.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.withdrawal {
         tables
-            .create_row("withdrawal", &format!("{}_{}", event.hash, event.block_number))
+            .create_row("withdrawal", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("hash", &event.hash)
             .set("recipient", &event.recipient)
+            .set("paid_tokens", event.paid_amounts.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("paid_amounts", event.paid_amounts.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
+            .set("vault", &event.vault)
+            .set("vault_mismatch", event.vault_mismatch)
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.refund {
         tables
-            .create_row("refund", &format!("{}_{}", event.hash, event.block_number))
+            .create_row("refund", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("hash", &event.hash)
             .set("recipient", &event.recipient)
+            .set("paid_tokens", event.paid_amounts.iter().map(|t| t.token.clone()).collect::<Vec<_>>())
+            .set("paid_amounts", event.paid_amounts.iter().map(|t| big_int(&t.amount)).collect::<Vec<_>>())
+            .set("vault", &event.vault)
+            .set("vault_mismatch", event.vault_mismatch)
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.intent_proof_challenged {
         tables
-            .create_row("intent_proof_challenged", &format!("{}_{}", event.intent_hash, event.block_number))
+            .create_row("intent_proof_challenged", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("intent_hash", &event.intent_hash)
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.fulfillment {
         tables
-            .create_row("fulfillment", &format!("{}_{}", event.hash, event.block_number))
+            .create_row("fulfillment", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("hash", &event.hash)
-            .set("source_chain_id", event.source_chain_id)
+            .set("source_chain_id", big_int(&event.source_chain_id))
             .set("prover", &event.prover)
             .set("claimant", &event.claimant)
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
     for event in events.order_filled {
         tables
-            .create_row("order_filled", &format!("{}_{}", event.order_id, event.block_number))
+            .create_row("order_filled", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
             .set("order_id", &event.order_id)
             .set("solver", &event.solver)
             .set("block_number", event.block_number)
             .set("tx_hash", &event.tx_hash)
-            .set("timestamp", event.timestamp);
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in events.to_be_proven {
+        tables
+            .create_row("to_be_proven", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("intent_hash", &event.intent_hash)
+            .set("source_chain_id", big_int(&event.source_chain_id))
+            .set("claimant", &event.claimant)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in events.hyper_instant_fulfillment {
+        tables
+            .create_row("hyper_instant_fulfillment", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("intent_hash", &event.intent_hash)
+            .set("source_chain_id", big_int(&event.source_chain_id))
+            .set("claimant", &event.claimant)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in events.add_to_batch {
+        tables
+            .create_row("add_to_batch", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("intent_hash", &event.intent_hash)
+            .set("source_chain_id", big_int(&event.source_chain_id))
+            .set("claimant", &event.claimant)
+            .set("prover", &event.prover)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in events.solver_whitelist_changed {
+        tables
+            .create_row("solver_whitelist_changed", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("solver", &event.solver)
+            .set("can_solve", event.can_solve)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
     }
 
+    for event in events.solving_is_public {
+        tables
+            .create_row("solving_is_public", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in events.mailbox_set {
+        tables
+            .create_row("mailbox_set", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("mailbox", &event.mailbox)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    write_decode_failures(&mut tables, &events.decode_failures);
+    write_unknown_logs(&mut tables, &events.unknown_logs);
+
+    prover::write_prover_events(&mut tables, &prover_events);
+    write_decode_failures(&mut tables, &prover_events.decode_failures);
+    write_unknown_logs(&mut tables, &prover_events.unknown_logs);
+
+    lifecycle::write_intents(&mut tables, &intent_state, &intent_status, &intents);
+    expiry::write_expired(&mut tables, &expired_intents);
+    latency::write_latencies(&mut tables, &intent_latency);
+    latency::write_percentiles(&mut tables, &latency_percentiles);
+    solvers::write_solvers(&mut tables, &solver_first_block, &solver_last_block, &solver_stats, &solver_rewards);
+    creators::write_creators(&mut tables, &creator_first_seen, &creator_last_seen, &creator_stats, &creator_rewards);
+    volume::write_token_volume(&mut tables, &token_volume, &token_intents);
+    corridors::write_corridors(&mut tables, &corridor_stats, &corridor_rewards);
+    funding::write_intent_funding(&mut tables, &intent_funding, &intents);
+    escrow::write_escrow_balances(&mut tables, &escrow_ledger);
+    tvl::write_tvl_daily(&mut tables, &tvl_samples, &tvl_daily_high, &tvl_daily_low);
+
     Ok(tables.to_entity_changes())
 }
 
-// Event decoding functions
-fn decode_intent_created_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<IntentCreated> {
-    if log.topics.len() < 4 {
-        return None;
-    }
-
-    // Parse indexed fields from topics
-    let intent_hash = format!("0x{}", hex::encode(&log.topics[1]));
-    let creator = format!("0x{}", hex::encode(&log.topics[2][12..]));
-    let prover = format!("0x{}", hex::encode(&log.topics[3][12..]));
-    
-    // For simplicity, parse basic fields from data
-    // In production, you'd want to use proper ABI decoding
-    let mut offset = 0;
-    
-    // Skip complex ABI decoding for now and extract basic fields
-    let salt = if log.data.len() >= 32 {
-        format!("0x{}", hex::encode(&log.data[offset..offset+32]))
-    } else {
-        "0x".to_string()
-    };
-    offset += 32;
+fn write_decode_failures(tables: &mut Tables, failures: &[DecodeFailure]) {
+    for failure in failures {
+        tables
+            .create_row("decode_failures", &event_id(failure.chain_id, failure.block_number, &failure.tx_hash, failure.log_index))
+            .set("chain_id", failure.chain_id)
+            .set("contract", &failure.contract)
+            .set("topic0", &failure.topic0)
+            .set("reason", &failure.reason)
+            .set("block_number", failure.block_number)
+            .set("tx_hash", &failure.tx_hash)
+            .set("timestamp", failure.timestamp)
+            .set("log_index", u64::from(failure.log_index))
+            .set("tx_index", u64::from(failure.tx_index))
+            .set("ordinal", failure.ordinal);
+    }
+}
 
-    let source_chain_id = if log.data.len() >= offset + 32 {
-        u64::from_be_bytes(log.data[offset+24..offset+32].try_into().unwrap_or([0; 8]))
-    } else {
-        0
-    };
-    offset += 32;
+fn write_unknown_logs(tables: &mut Tables, logs: &[UnknownLog]) {
+    for log in logs {
+        tables
+            .create_row("unknown_events", &event_id(log.chain_id, log.block_number, &log.tx_hash, log.log_index))
+            .set("chain_id", log.chain_id)
+            .set("contract", &log.contract)
+            .set("topic0", log.topics.first().cloned().unwrap_or_default())
+            .set("topics", log.topics.clone())
+            .set("data", &log.data)
+            .set("block_number", log.block_number)
+            .set("tx_hash", &log.tx_hash)
+            .set("timestamp", log.timestamp)
+            .set("log_index", u64::from(log.log_index))
+            .set("tx_index", u64::from(log.tx_index))
+            .set("ordinal", log.ordinal);
+    }
+}
 
-    let destination_chain_id = if log.data.len() >= offset + 32 {
-        u64::from_be_bytes(log.data[offset+24..offset+32].try_into().unwrap_or([0; 8]))
+// Decode a log emitted by the IntentSource into the matching IntentEvents list.
+// Returns Ok(false) when topic0 isn't one of its known events.
+fn decode_intent_source_log(log: &eth::Log, ctx: &LogContext, params: &Params, events: &mut IntentEvents) -> Result<bool, String> {
+    if let Some(event) = decode_intent_created_event(log, ctx, params)? {
+        events.intent_created.push(event);
+    } else if let Some(event) = decode_intent_funded_event(log, ctx)? {
+        events.intent_funded.push(event);
+    } else if let Some(event) = decode_intent_partially_funded_event(log, ctx)? {
+        events.intent_partially_funded.push(event);
+    } else if let Some(event) = decode_withdrawal_event(log, ctx)? {
+        events.withdrawal.push(event);
+    } else if let Some(event) = decode_refund_event(log, ctx)? {
+        events.refund.push(event);
+    } else if let Some(event) = decode_intent_proof_challenged_event(log, ctx)? {
+        events.intent_proof_challenged.push(event);
     } else {
-        0
-    };
-    offset += 32;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
 
-    let inbox_address = if log.data.len() >= offset + 32 {
-        format!("0x{}", hex::encode(&log.data[offset+12..offset+32]))
+// Decode a log emitted by the Inbox into the matching IntentEvents list.
+// Returns Ok(false) when topic0 isn't one of its known events.
+fn decode_inbox_log(log: &eth::Log, ctx: &LogContext, events: &mut IntentEvents) -> Result<bool, String> {
+    if let Some(event) = decode_fulfillment_event(log, ctx)? {
+        events.fulfillment.push(event);
+    } else if let Some(event) = decode_order_filled_event(log, ctx)? {
+        events.order_filled.push(event);
+    } else if let Some(event) = decode_to_be_proven_event(log, ctx)? {
+        events.to_be_proven.push(event);
+    } else if let Some(event) = decode_hyper_instant_fulfillment_event(log, ctx)? {
+        events.hyper_instant_fulfillment.push(event);
+    } else if let Some(event) = decode_add_to_batch_event(log, ctx)? {
+        events.add_to_batch.push(event);
+    } else if let Some(event) = decode_solver_whitelist_changed_event(log, ctx)? {
+        events.solver_whitelist_changed.push(event);
+    } else if let Some(event) = decode_solving_is_public_event(log, ctx)? {
+        events.solving_is_public.push(event);
+    } else if let Some(event) = decode_mailbox_set_event(log, ctx)? {
+        events.mailbox_set.push(event);
     } else {
-        "0x".to_string()
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+// Topic0 of every decoded event. Abigen keeps its TOPIC_ID private to the
+// generated module, so the hashes are listed here and checked against the
+// event signatures in the tests below. Its match_log also checks the topic
+// count and data length, so it can't tell a different event from a log
+// whose layout changed.
+trait EventTopic: Event {
+    const TOPIC: [u8; 32];
+}
+
+macro_rules! event_topics {
+    ($($event:ty => $topic:literal),* $(,)?) => {
+        $(impl EventTopic for $event {
+            const TOPIC: [u8; 32] = topic($topic);
+        })*
     };
-    
-    // For complex arrays like TokenAmount and Call, we'll skip detailed parsing
-    // and leave them empty for now - proper ABI decoding would be needed
-    
-    Some(IntentCreated {
-        intent_hash,
-        salt,
-        source_chain_id,
-        destination_chain_id,
-        inbox_address,
-        route_tokens: vec![], // TODO: Implement proper ABI decoding
-        calls: vec![], // TODO: Implement proper ABI decoding
-        creator,
-        prover,
-        deadline: 0, // TODO: Extract from data
-        native_value: 0, // TODO: Extract from data
-        reward_tokens: vec![], // TODO: Implement proper ABI decoding
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
 }
 
-fn decode_intent_funded_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<IntentFunded> {
-    if log.data.len() < 64 {
-        return None;
+event_topics!(
+    intent_source::events::IntentCreated => "d802f2610d0c85b3f19be4413f3cf49de1d4e787edecd538274437a5b9aa648d",
+    intent_source::events::IntentFunded => "2da42efda5225344c30e729dc0eafc2e56292ac9b9b5c2b16e0e74c86ea5921d",
+    intent_source::events::IntentPartiallyFunded => "97cf148f008486c490afd3b522e2398d5039247c7fffe81fcae2a8c6ee622103",
+    intent_source::events::Withdrawal => "6653a45d3871e4110fa55dac0269f9f93a6d9078d402f7153594e50573d7f0cd",
+    intent_source::events::Refund => "0ba6f12b978882904e7444c7a8fcadd2d9f692a6a97aa18e5fb44c3bbc580123",
+    intent_source::events::IntentProofChallenged => "69f2194063569059c6cc65d4599038f27aa9590bbb3f008178b6d20c453b9e82",
+    inbox::events::Fulfillment => "4a817ec64beb8020b3e400f30f3b458110d5765d7a9d1ace4e68754ed2d082de",
+    inbox::events::OrderFilled => "0555709e59fb225fcf12cc582a9e5f7fd8eea54c91f3dc500ab9d8c37c507770",
+    inbox::events::ToBeProven => "160fbf39d03c171cfc82812374f495fff22339e6f2482ce8366139b1710d003b",
+    inbox::events::HyperInstantFulfillment => "15299a9532f9e616b46fb1fff3ff56426e75c2cab158a32a4cae83547bfa3f74",
+    inbox::events::AddToBatch => "14beba1e8723c2a6b7a4cc9576347da53f1b79e39366f0d4f8b7a6af344eb71f",
+    inbox::events::SolverWhitelistChanged => "3f3a0c23a11da402767747df1ed29891ffc0a54d42f3e8c767548cd45b7dd678",
+    inbox::events::SolvingIsPublic => "547ced8bfaeeb516dd99a65ffc844c3eacbdc19ce3857d1bd0fc8108207c6808",
+    inbox::events::MailboxSet => "7a61e573722ff8b205c8962b59d37e7d30573f368965597a844a94872204ebd7",
+    abi::prover::events::IntentProven => "2b45193f790d995b36e39c4104dd1b49df6fc851b6f6ae60f2072724735b5b43",
+    abi::prover::events::IntentAlreadyProven => "c86ca07015d7e87a46a98098d36c9fc68bc3120761e5c7a2023fc6c6869e5611",
+);
+
+// 64 lowercase hex digits to bytes, evaluated at compile time
+const fn topic(hex: &str) -> [u8; 32] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            _ => panic!("topic0 must be lowercase hex"),
+        }
     }
 
-    // Both fields are in data (not indexed)
-    let intent_hash = format!("0x{}", hex::encode(&log.data[0..32]));
-    let funder = format!("0x{}", hex::encode(&log.data[44..64])); // Skip padding
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 64, "topic0 must be 32 bytes");
+    let mut bytes = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        bytes[i] = nibble(hex[2 * i]) << 4 | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    bytes
+}
 
-    Some(IntentFunded {
-        intent_hash,
-        funder,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+fn has_topic<E: EventTopic>(log: &eth::Log) -> bool {
+    log.topics.first().map(Vec::as_slice) == Some(&E::TOPIC[..])
 }
 
-fn decode_intent_partially_funded_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<IntentPartiallyFunded> {
-    if log.data.len() < 64 {
-        return None;
+// Event decoding functions. Ok(None) means topic0 belongs to a different
+// event; Err means topic0 matched but the log doesn't have the event's shape
+// (match_log checks the topic count and data length) or the generated
+// decoder rejected it.
+fn decode_log<E: EventTopic>(log: &eth::Log) -> Result<Option<E>, String> {
+    if !has_topic::<E>(log) {
+        return Ok(None);
     }
+    if !E::match_log(log) {
+        return Err(format!("{}: unexpected topic count or data length", E::NAME));
+    }
+    E::decode(log)
+        .map(Some)
+        .map_err(|err| format!("{}: {}", E::NAME, err))
+}
 
-    // Both fields are in data (not indexed)
-    let intent_hash = format!("0x{}", hex::encode(&log.data[0..32]));
-    let funder = format!("0x{}", hex::encode(&log.data[44..64])); // Skip padding
+fn decode_intent_created_event(log: &eth::Log, ctx: &LogContext, params: &Params) -> Result<Option<IntentCreated>, String> {
+    let Some(event) = decode_log::<intent_source::events::IntentCreated>(log)? else {
+        return Ok(None);
+    };
+    let hashes = hashes::intent_hashes(&event);
 
-    Some(IntentPartiallyFunded {
-        intent_hash,
-        funder,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+    Ok(Some(IntentCreated {
+        intent_hash: hex_string(&event.hash),
+        route_hash: hex_string(&hashes.route_hash),
+        reward_hash: hex_string(&hashes.reward_hash),
+        computed_intent_hash: hex_string(&hashes.intent_hash),
+        hash_mismatch: hashes.intent_hash != event.hash,
+        intent_source: hex_string(&log.address),
+        vault: params
+            .vault_creation_code
+            .as_ref()
+            .map(|code| hex_string(&hashes::vault_address(&log.address, &event, &hashes.route_hash, code)))
+            .unwrap_or_default(),
+        salt: hex_string(&event.salt),
+        source_chain_id: event.source.to_string(),
+        destination_chain_id: event.destination.to_string(),
+        inbox_address: hex_string(&event.inbox),
+        route_tokens: token_amounts(event.route_tokens),
+        calls: event
+            .calls
+            .into_iter()
+            .map(|(target, data, value)| Call {
+                target: hex_string(&target),
+                data: hex_string(&data),
+                value: value.to_string(),
+            })
+            .collect(),
+        creator: hex_string(&event.creator),
+        prover: hex_string(&event.prover),
+        deadline: event.deadline.to_string(),
+        native_value: event.native_value.to_string(),
+        reward_tokens: token_amounts(event.reward_tokens),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
 }
 
-fn decode_withdrawal_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<Withdrawal> {
-    if log.topics.len() < 2 || log.data.len() < 32 {
-        return None;
-    }
+fn decode_intent_funded_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<IntentFunded>, String> {
+    let Some(event) = decode_log::<intent_source::events::IntentFunded>(log)? else {
+        return Ok(None);
+    };
 
-    // hash is in data, recipient is indexed
-    let hash = format!("0x{}", hex::encode(&log.data[0..32]));
-    let recipient = format!("0x{}", hex::encode(&log.topics[1][12..]));
+    Ok(Some(IntentFunded {
+        intent_hash: hex_string(&event.intent_hash),
+        funder: hex_string(&event.funder),
+        // Filled in from the transaction by funding::map_intent_amounts
+        vault: String::new(),
+        funded_amounts: Vec::new(),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
 
-    Some(Withdrawal {
-        hash,
-        recipient,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+fn decode_intent_partially_funded_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<IntentPartiallyFunded>, String> {
+    let Some(event) = decode_log::<intent_source::events::IntentPartiallyFunded>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(IntentPartiallyFunded {
+        intent_hash: hex_string(&event.intent_hash),
+        funder: hex_string(&event.funder),
+        // Filled in from the transaction by funding::map_intent_amounts
+        vault: String::new(),
+        funded_amounts: Vec::new(),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
 }
 
-fn decode_refund_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<Refund> {
-    if log.topics.len() < 2 || log.data.len() < 32 {
-        return None;
-    }
+fn decode_withdrawal_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<Withdrawal>, String> {
+    let Some(event) = decode_log::<intent_source::events::Withdrawal>(log)? else {
+        return Ok(None);
+    };
 
-    // hash is in data, recipient is indexed
-    let hash = format!("0x{}", hex::encode(&log.data[0..32]));
-    let recipient = format!("0x{}", hex::encode(&log.topics[1][12..]));
+    Ok(Some(Withdrawal {
+        hash: hex_string(&event.hash),
+        recipient: hex_string(&event.recipient),
+        // Filled in from the transaction by funding::map_intent_amounts
+        paid_amounts: Vec::new(),
+        vault: String::new(),
+        vault_mismatch: false,
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
 
-    Some(Refund {
-        hash,
-        recipient,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+fn decode_refund_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<Refund>, String> {
+    let Some(event) = decode_log::<intent_source::events::Refund>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Refund {
+        hash: hex_string(&event.hash),
+        recipient: hex_string(&event.recipient),
+        // Filled in from the transaction by funding::map_intent_amounts
+        paid_amounts: Vec::new(),
+        vault: String::new(),
+        vault_mismatch: false,
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
 }
 
-fn decode_intent_proof_challenged_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<IntentProofChallenged> {
-    if log.data.len() < 32 {
-        return None;
-    }
+fn decode_intent_proof_challenged_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<IntentProofChallenged>, String> {
+    let Some(event) = decode_log::<intent_source::events::IntentProofChallenged>(log)? else {
+        return Ok(None);
+    };
 
-    // Only intentHash field in data (not indexed)
-    let intent_hash = format!("0x{}", hex::encode(&log.data[0..32]));
+    Ok(Some(IntentProofChallenged {
+        intent_hash: hex_string(&event.intent_hash),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
 
-    Some(IntentProofChallenged {
-        intent_hash,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+fn decode_fulfillment_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<Fulfillment>, String> {
+    let Some(event) = decode_log::<inbox::events::Fulfillment>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Fulfillment {
+        hash: hex_string(&event.hash),
+        source_chain_id: event.source_chain_id.to_string(),
+        prover: hex_string(&event.prover),
+        claimant: hex_string(&event.claimant),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_order_filled_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<OrderFilled>, String> {
+    let Some(event) = decode_log::<inbox::events::OrderFilled>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(OrderFilled {
+        order_id: hex_string(&event.order_id),
+        solver: hex_string(&event.solver),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_to_be_proven_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<ToBeProven>, String> {
+    let Some(event) = decode_log::<inbox::events::ToBeProven>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(ToBeProven {
+        intent_hash: hex_string(&event.hash),
+        source_chain_id: event.source_chain_id.to_string(),
+        claimant: hex_string(&event.claimant),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_hyper_instant_fulfillment_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<HyperInstantFulfillment>, String> {
+    let Some(event) = decode_log::<inbox::events::HyperInstantFulfillment>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(HyperInstantFulfillment {
+        intent_hash: hex_string(&event.hash),
+        source_chain_id: event.source_chain_id.to_string(),
+        claimant: hex_string(&event.claimant),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_add_to_batch_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<AddToBatch>, String> {
+    let Some(event) = decode_log::<inbox::events::AddToBatch>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(AddToBatch {
+        intent_hash: hex_string(&event.hash),
+        source_chain_id: event.source_chain_id.to_string(),
+        claimant: hex_string(&event.claimant),
+        prover: hex_string(&event.prover),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_solver_whitelist_changed_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<SolverWhitelistChanged>, String> {
+    let Some(event) = decode_log::<inbox::events::SolverWhitelistChanged>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(SolverWhitelistChanged {
+        solver: hex_string(&event.solver),
+        can_solve: event.can_solve,
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
 }
 
-fn decode_fulfillment_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<Fulfillment> {
-    if log.topics.len() < 4 || log.data.len() < 32 {
-        return None;
+fn decode_solving_is_public_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<SolvingIsPublic>, String> {
+    // The event has no fields; only its position is recorded
+    if decode_log::<inbox::events::SolvingIsPublic>(log)?.is_none() {
+        return Ok(None);
     }
 
-    // _hash, _sourceChainID, _prover are indexed; _claimant is in data
-    let hash = format!("0x{}", hex::encode(&log.topics[1]));
-    let source_chain_id = u64::from_be_bytes(log.topics[2][24..].try_into().ok()?);
-    let prover = format!("0x{}", hex::encode(&log.topics[3][12..]));
-    let claimant = format!("0x{}", hex::encode(&log.data[12..32])); // Skip padding
+    Ok(Some(SolvingIsPublic {
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
 
-    Some(Fulfillment {
-        hash,
-        source_chain_id,
-        prover,
-        claimant,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
+fn decode_mailbox_set_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<MailboxSet>, String> {
+    let Some(event) = decode_log::<inbox::events::MailboxSet>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(MailboxSet {
+        mailbox: hex_string(&event.mailbox),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+// Row id for anything emitted from a single log. The tx hash and log index
+// identify the log; chain and block keep ids unique and sortable when
+// several chains share one database.
+fn event_id(chain_id: u64, block_number: u64, tx_hash: &str, log_index: u32) -> String {
+    format!("{}_{}_{}_{}", chain_id, block_number, tx_hash, log_index)
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+// uint256 values travel through IntentEvents as decimal strings produced by
+// BigInt::to_string, so parsing them back is lossless. Anything else is
+// corrupt input: the module fails on it instead of counting it as zero.
+fn big_int(value: &str) -> BigInt {
+    value
+        .parse()
+        .unwrap_or_else(|_| panic!("invalid uint256 {:?}: expected a decimal string", value))
 }
 
-fn decode_order_filled_event(log: &eth::Log, block_number: u64, tx_hash: &str, timestamp: u64) -> Option<OrderFilled> {
-    if log.data.len() < 64 {
-        return None;
+// TokenAmount[] decodes to (token, amount) tuples
+fn token_amounts(tokens: Vec<(Vec<u8>, BigInt)>) -> Vec<TokenAmount> {
+    tokens
+        .into_iter()
+        .map(|(token, amount)| TokenAmount {
+            token: hex_string(&token),
+            amount: amount.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature_topic(signature: &str) -> [u8; 32] {
+        hashes::keccak256(signature.as_bytes())
     }
 
-    // Both _orderId and _solver are in data (not indexed)
-    let order_id = format!("0x{}", hex::encode(&log.data[0..32]));
-    let solver = format!("0x{}", hex::encode(&log.data[44..64])); // Skip padding
+    // Canonical signatures from abi/*.json, tuples expanded
+    #[test]
+    fn topics_match_event_signatures() {
+        let topics = [
+            (
+                intent_source::events::IntentCreated::TOPIC,
+                "IntentCreated(bytes32,bytes32,uint256,uint256,address,(address,uint256)[],(address,bytes,uint256)[],address,address,uint256,uint256,(address,uint256)[])",
+            ),
+            (intent_source::events::IntentFunded::TOPIC, "IntentFunded(bytes32,address)"),
+            (intent_source::events::IntentPartiallyFunded::TOPIC, "IntentPartiallyFunded(bytes32,address)"),
+            (intent_source::events::Withdrawal::TOPIC, "Withdrawal(bytes32,address)"),
+            (intent_source::events::Refund::TOPIC, "Refund(bytes32,address)"),
+            (intent_source::events::IntentProofChallenged::TOPIC, "IntentProofChallenged(bytes32)"),
+            (inbox::events::Fulfillment::TOPIC, "Fulfillment(bytes32,uint256,address,address)"),
+            (inbox::events::OrderFilled::TOPIC, "OrderFilled(bytes32,address)"),
+            (inbox::events::ToBeProven::TOPIC, "ToBeProven(bytes32,uint256,address)"),
+            (inbox::events::HyperInstantFulfillment::TOPIC, "HyperInstantFulfillment(bytes32,uint256,address)"),
+            (inbox::events::AddToBatch::TOPIC, "AddToBatch(bytes32,uint256,address,address)"),
+            (inbox::events::SolverWhitelistChanged::TOPIC, "SolverWhitelistChanged(address,bool)"),
+            (inbox::events::SolvingIsPublic::TOPIC, "SolvingIsPublic()"),
+            (inbox::events::MailboxSet::TOPIC, "MailboxSet(address)"),
+            (abi::prover::events::IntentProven::TOPIC, "IntentProven(bytes32,address)"),
+            (abi::prover::events::IntentAlreadyProven::TOPIC, "IntentAlreadyProven(bytes32)"),
+        ];
 
-    Some(OrderFilled {
-        order_id,
-        solver,
-        block_number,
-        tx_hash: tx_hash.to_string(),
-        timestamp,
-    })
-}
\ No newline at end of file
+        for (topic, signature) in topics {
+            assert_eq!(topic, signature_topic(signature), "{}", signature);
+        }
+    }
+
+    // Withdrawal(bytes32 hash, address indexed recipient): two topics and
+    // one word of data
+    #[test]
+    fn rejects_logs_with_the_wrong_shape() {
+        let withdrawal = |topics: usize| eth::Log {
+            topics: [intent_source::events::Withdrawal::TOPIC.to_vec()]
+                .into_iter()
+                .chain(std::iter::repeat(vec![0u8; 32]).take(topics - 1))
+                .collect(),
+            data: vec![0u8; 32],
+            ..Default::default()
+        };
+
+        assert!(decode_log::<intent_source::events::Withdrawal>(&withdrawal(2)).unwrap().is_some());
+        assert_eq!(
+            decode_log::<intent_source::events::Withdrawal>(&withdrawal(3)).unwrap_err(),
+            "Withdrawal: unexpected topic count or data length"
+        );
+        assert!(decode_log::<intent_source::events::Refund>(&withdrawal(2)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::pb::intentsource::v1::{IntentCreated, IntentEvents, IntentStatus, IntentTransition, ProverEvents};
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::key;
+use substreams::prelude::*;
+use substreams::store::{DeltaInt64, DeltaProto, Deltas, StoreGetProto, StoreMaxInt64, StoreSetIfNotExistsProto};
+use substreams_entity_change::tables::Tables;
+
+// Keeps the first transition of every intent into each status, keyed
+// `intent:{intent_hash}:{status}`. Later repeats (e.g. a second partial
+// funding) don't change the stored transition.
+#[substreams::handlers::store]
+fn store_intent_state(events: IntentEvents, prover_events: ProverEvents, store: StoreSetIfNotExistsProto<IntentTransition>) {
+    for transition in transitions(&events, &prover_events) {
+        store.set_if_not_exists(
+            transition.ordinal,
+            state_key(&transition.intent_hash, transition.status()),
+            &transition,
+        );
+    }
+}
+
+// IntentCreated of every intent published on the indexed chain, keyed
+// `intent:{intent_hash}`, for modules that need an intent's route or rewards
+// when a later event only carries its hash
+#[substreams::handlers::store]
+fn store_intents(events: IntentEvents, store: StoreSetIfNotExistsProto<IntentCreated>) {
+    for event in &events.intent_created {
+        store.set_if_not_exists(event.ordinal, intent_key(&event.intent_hash), event);
+    }
+}
+
+// Furthest status reached by every intent published on the indexed chain, as
+// its rank in STATUS_ORDER, keyed `intent:{intent_hash}`. Transitions of
+// intents created elsewhere (fulfillments of inbound intents) are left out.
+#[substreams::handlers::store]
+fn store_intent_status(
+    events: IntentEvents,
+    prover_events: ProverEvents,
+    intents: StoreGetProto<IntentCreated>,
+    store: StoreMaxInt64,
+) {
+    for transition in transitions(&events, &prover_events) {
+        if intents.get_last(intent_key(&transition.intent_hash)).is_none() {
+            continue;
+        }
+        store.max(transition.ordinal, intent_key(&transition.intent_hash), status_rank(transition.status()));
+    }
+}
+
+// Statuses from first to last; an intent's `status` only moves down this
+// list. This is the only lifecycle ranking, IntentStatus values aren't in
+// lifecycle order.
+const STATUS_ORDER: [IntentStatus; 8] = [
+    IntentStatus::Created,
+    IntentStatus::PartiallyFunded,
+    IntentStatus::Funded,
+    IntentStatus::Fulfilled,
+    IntentStatus::Proven,
+    IntentStatus::Challenged,
+    IntentStatus::Withdrawn,
+    IntentStatus::Refunded,
+];
+
+fn status_rank(status: IntentStatus) -> i64 {
+    STATUS_ORDER
+        .iter()
+        .position(|s| *s == status)
+        .map(|i| i as i64 + 1)
+        .unwrap_or(0)
+}
+
+fn ranked_status(rank: i64) -> IntentStatus {
+    usize::try_from(rank - 1)
+        .ok()
+        .and_then(|i| STATUS_ORDER.get(i).copied())
+        .unwrap_or(IntentStatus::Unspecified)
+}
+
+pub fn intent_key(intent_hash: &str) -> String {
+    format!("intent:{}", intent_hash)
+}
+
+pub fn state_key(intent_hash: &str, status: IntentStatus) -> String {
+    format!("intent:{}:{}", intent_hash, status_name(status))
+}
+
+pub fn status_name(status: IntentStatus) -> &'static str {
+    match status {
+        IntentStatus::Unspecified => "unspecified",
+        IntentStatus::Created => "created",
+        IntentStatus::PartiallyFunded => "partially_funded",
+        IntentStatus::Funded => "funded",
+        IntentStatus::Fulfilled => "fulfilled",
+        IntentStatus::Proven => "proven",
+        IntentStatus::Challenged => "challenged",
+        IntentStatus::Withdrawn => "withdrawn",
+        IntentStatus::Refunded => "refunded",
+    }
+}
+
+// Every lifecycle step in the block, in log order
+fn transitions(events: &IntentEvents, prover_events: &ProverEvents) -> Vec<IntentTransition> {
+    let mut transitions = Vec::new();
+
+    let mut push = |intent_hash: &str, status: IntentStatus, chain_id: u64, block_number: u64, tx_hash: &str, timestamp: u64, ordinal: u64| {
+        transitions.push(IntentTransition {
+            intent_hash: intent_hash.to_string(),
+            status: status as i32,
+            chain_id,
+            block_number,
+            tx_hash: tx_hash.to_string(),
+            timestamp,
+            ordinal,
+        })
+    };
+
+    for e in &events.intent_created {
+        push(&e.intent_hash, IntentStatus::Created, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.intent_partially_funded {
+        push(&e.intent_hash, IntentStatus::PartiallyFunded, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.intent_funded {
+        push(&e.intent_hash, IntentStatus::Funded, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.fulfillment {
+        push(&e.hash, IntentStatus::Fulfilled, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &prover_events.intent_proven {
+        push(&e.intent_hash, IntentStatus::Proven, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.intent_proof_challenged {
+        push(&e.intent_hash, IntentStatus::Challenged, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.withdrawal {
+        push(&e.hash, IntentStatus::Withdrawn, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+    for e in &events.refund {
+        push(&e.hash, IntentStatus::Refunded, e.chain_id, e.block_number, &e.tx_hash, e.timestamp, e.ordinal);
+    }
+
+    transitions.sort_by_key(|t| t.ordinal);
+    transitions
+}
+
+// One `intents` row per intent published on the indexed chain, keyed by
+// intent hash. Each transition records when it happened as `{status}_block`,
+// `{status}_timestamp` and `{status}_chain_id`; `status` follows
+// store_intent_status, so it never moves back. Transitions of intents
+// without a row (created on another chain or before the start block) are
+// skipped: several chains share the table, and the row belongs to the
+// source chain.
+pub fn write_intents(
+    tables: &mut Tables,
+    deltas: &Deltas<DeltaProto<IntentTransition>>,
+    status_deltas: &Deltas<DeltaInt64>,
+    intents: &StoreGetProto<IntentCreated>,
+) {
+    for delta in deltas.deltas.iter() {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+
+        let transition = &delta.new_value;
+        let status = status_name(transition.status());
+        let row = if transition.status() == IntentStatus::Created {
+            tables.create_row("intents", &transition.intent_hash)
+        } else if intents.get_last(intent_key(&transition.intent_hash)).is_some() {
+            tables.update_row("intents", &transition.intent_hash)
+        } else {
+            continue;
+        };
+
+        row.set("intent_hash", &transition.intent_hash)
+            .set(&format!("{}_block", status), transition.block_number)
+            .set(&format!("{}_timestamp", status), transition.timestamp)
+            .set(&format!("{}_chain_id", status), transition.chain_id);
+    }
+
+    for delta in status_deltas.deltas.iter() {
+        tables
+            .update_row("intents", key::segment_at(&delta.key, 1))
+            .set("status", status_name(ranked_status(delta.new_value)));
+    }
+}
@@ -0,0 +1,293 @@
+use substreams::errors::Error;
+
+// Event kinds that can be selected with `events=`, named after their tables
+pub const EVENT_KINDS: &[&str] = &[
+    "intent_created",
+    "intent_funded",
+    "intent_partially_funded",
+    "withdrawal",
+    "refund",
+    "intent_proof_challenged",
+    "fulfillment",
+    "order_filled",
+    "to_be_proven",
+    "hyper_instant_fulfillment",
+    "add_to_batch",
+    "solver_whitelist_changed",
+    "solving_is_public",
+    "mailbox_set",
+    "intent_proven",
+    "intent_already_proven",
+];
+
+// Module params, passed as a query string, e.g.
+// `chain_id=8453&intentsource=0x2020...@1234,0x3030...&inbox=0x04c8...@1234&events=intent_created,fulfillment`
+// `chain_id` is the chain being indexed and is stamped on every event.
+// `prover` optionally lists prover contracts to watch in addition to the
+// ones discovered from indexed intents. `vault_creation_code` optionally
+// gives the deployment's IntentVault creation bytecode, used to derive each
+// intent's CREATE2 vault.
+// Address keys and `events` may repeat and values may be comma separated;
+// an optional `@block` suffix gives the contract's deployment block.
+// `chain_id` and `vault_creation_code` may only be given once. `events` is
+// optional and defaults to every kind.
+#[derive(Debug, Default)]
+pub struct Params {
+    pub chain_id: u64,
+    pub intent_sources: Vec<Contract>,
+    pub inboxes: Vec<Contract>,
+    pub provers: Vec<Contract>,
+    pub vault_creation_code: Option<Vec<u8>>,
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct Contract {
+    pub address: Vec<u8>,
+    // Logs are only decoded from this block on
+    pub start_block: u64,
+}
+
+impl Contract {
+    fn matches(&self, address: &[u8], block_number: u64) -> bool {
+        self.address == address && block_number >= self.start_block
+    }
+}
+
+impl Params {
+    pub fn parse(params: &str) -> Result<Self, Error> {
+        let mut parsed = Params::default();
+        let mut chain_id = None;
+
+        for pair in params.split('&').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::msg(format!("invalid param '{}': expected key=value", pair)))?;
+            let values = value.split(',').map(str::trim).filter(|value| !value.is_empty());
+
+            match key.trim() {
+                "chain_id" => {
+                    if chain_id.is_some() {
+                        return Err(Error::msg("invalid param chain_id: given more than once"));
+                    }
+                    let id = value
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|_| Error::msg(format!("invalid param chain_id: '{}' is not a chain id", value)))?;
+                    chain_id = Some(id);
+                },
+                "intentsource" => {
+                    for value in values {
+                        parsed.intent_sources.push(parse_contract(key, value)?);
+                    }
+                },
+                "inbox" => {
+                    for value in values {
+                        parsed.inboxes.push(parse_contract(key, value)?);
+                    }
+                },
+                "prover" => {
+                    for value in values {
+                        parsed.provers.push(parse_contract(key, value)?);
+                    }
+                },
+                "vault_creation_code" => {
+                    if parsed.vault_creation_code.is_some() {
+                        return Err(Error::msg("invalid param vault_creation_code: given more than once"));
+                    }
+                    parsed.vault_creation_code = Some(parse_bytecode(key, value.trim())?);
+                },
+                "events" => {
+                    let events = parsed.events.get_or_insert_with(Vec::new);
+                    for value in values {
+                        if !EVENT_KINDS.contains(&value) {
+                            return Err(Error::msg(format!(
+                                "invalid param events: unknown event kind '{}', expected one of {}",
+                                value,
+                                EVENT_KINDS.join(", ")
+                            )));
+                        }
+                        events.push(value.to_string());
+                    }
+                },
+                other => {
+                    return Err(Error::msg(format!(
+                        "invalid param '{}': unknown key, expected chain_id, intentsource, inbox, prover, vault_creation_code or events",
+                        other
+                    )))
+                },
+            }
+        }
+
+        parsed.chain_id = chain_id.ok_or_else(|| Error::msg("invalid params: chain_id is required"))?;
+        if parsed.intent_sources.is_empty() && parsed.inboxes.is_empty() && parsed.provers.is_empty() {
+            return Err(Error::msg(
+                "invalid params: at least one intentsource, inbox or prover address is required",
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    // Contracts whose deployment block hasn't been reached are skipped
+    pub fn is_intent_source(&self, address: &[u8], block_number: u64) -> bool {
+        self.intent_sources.iter().any(|c| c.matches(address, block_number))
+    }
+
+    pub fn is_inbox(&self, address: &[u8], block_number: u64) -> bool {
+        self.inboxes.iter().any(|c| c.matches(address, block_number))
+    }
+
+    pub fn is_prover(&self, address: &[u8], block_number: u64) -> bool {
+        self.provers.iter().any(|c| c.matches(address, block_number))
+    }
+
+    pub fn emits(&self, kind: &str) -> bool {
+        match &self.events {
+            Some(kinds) => kinds.iter().any(|k| k == kind),
+            None => true,
+        }
+    }
+}
+
+// `0x<address>` or `0x<address>@<deployment block>`
+fn parse_contract(key: &str, value: &str) -> Result<Contract, Error> {
+    let (address, start_block) = match value.split_once('@') {
+        Some((address, block)) => {
+            let block = block.trim().parse::<u64>().map_err(|_| {
+                Error::msg(format!("invalid param {}: '{}' has an invalid deployment block", key, value))
+            })?;
+            (address.trim(), block)
+        },
+        None => (value, 0),
+    };
+
+    Ok(Contract {
+        address: parse_address(key, address)?,
+        start_block,
+    })
+}
+
+fn parse_address(key: &str, value: &str) -> Result<Vec<u8>, Error> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    match hex::decode(digits) {
+        Ok(address) if address.len() == 20 => Ok(address),
+        _ => Err(Error::msg(format!(
+            "invalid param {}: '{}' is not a 20-byte hex address",
+            key, value
+        ))),
+    }
+}
+
+fn parse_bytecode(key: &str, value: &str) -> Result<Vec<u8>, Error> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    match hex::decode(digits) {
+        Ok(code) if !code.is_empty() => Ok(code),
+        _ => Err(Error::msg(format!("invalid param {}: '{}' is not hex bytecode", key, value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTENT_SOURCE: &str = "0x2020ae689ed3e017450280cea110d0ef6e640da4";
+    const INBOX: &str = "0x04c816032a076df65b411bb3f31c8d569d411ee2";
+
+    fn error(params: &str) -> String {
+        Params::parse(params).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_contracts_events_and_vault_creation_code() {
+        let params = Params::parse(&format!(
+            "chain_id=8453&intentsource={}@100,{}&inbox={}@200&events=intent_created,fulfillment&vault_creation_code=0x6080604052",
+            INTENT_SOURCE, INBOX, INBOX
+        ))
+        .unwrap();
+
+        assert_eq!(params.chain_id, 8453);
+        assert_eq!(params.intent_sources.len(), 2);
+        assert_eq!(params.intent_sources[0].start_block, 100);
+        assert_eq!(params.intent_sources[1].start_block, 0);
+        assert_eq!(params.vault_creation_code, Some(vec![0x60, 0x80, 0x60, 0x40, 0x52]));
+
+        let inbox = hex::decode(&INBOX[2..]).unwrap();
+        assert!(!params.is_inbox(&inbox, 199));
+        assert!(params.is_inbox(&inbox, 200));
+        assert!(params.emits("fulfillment"));
+        assert!(!params.emits("withdrawal"));
+    }
+
+    #[test]
+    fn requires_chain_id() {
+        assert_eq!(error(&format!("intentsource={}", INTENT_SOURCE)), "invalid params: chain_id is required");
+        assert_eq!(
+            error(&format!("chain_id=base&intentsource={}", INTENT_SOURCE)),
+            "invalid param chain_id: 'base' is not a chain id"
+        );
+    }
+
+    #[test]
+    fn requires_a_contract() {
+        assert_eq!(
+            error("chain_id=8453"),
+            "invalid params: at least one intentsource, inbox or prover address is required"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_contracts() {
+        assert_eq!(
+            error(&format!("chain_id=8453&inbox={}@latest", INBOX)),
+            format!("invalid param inbox: '{}@latest' has an invalid deployment block", INBOX)
+        );
+        assert_eq!(
+            error("chain_id=8453&inbox=0x04c8@100"),
+            "invalid param inbox: '0x04c8' is not a 20-byte hex address"
+        );
+        assert_eq!(error("chain_id=8453&inbox"), "invalid param 'inbox': expected key=value");
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_event_kinds() {
+        assert!(error(&format!("chain_id=8453&contract={}", INBOX)).starts_with("invalid param 'contract': unknown key"));
+        assert!(error(&format!("chain_id=8453&inbox={}&events=intent_created,transfer", INBOX))
+            .starts_with("invalid param events: unknown event kind 'transfer'"));
+    }
+
+    #[test]
+    fn rejects_bad_vault_creation_code() {
+        assert_eq!(
+            error(&format!("chain_id=8453&inbox={}&vault_creation_code=0x608", INBOX)),
+            "invalid param vault_creation_code: '0x608' is not hex bytecode"
+        );
+        assert_eq!(
+            error(&format!("chain_id=8453&inbox={}&vault_creation_code=0x", INBOX)),
+            "invalid param vault_creation_code: '0x' is not hex bytecode"
+        );
+    }
+
+    #[test]
+    fn merges_repeated_lists_and_rejects_repeated_scalars() {
+        let params = Params::parse(&format!(
+            "chain_id=8453&inbox={}&inbox={}@5&events=intent_created&events=refund",
+            INBOX, INTENT_SOURCE
+        ))
+        .unwrap();
+        assert_eq!(params.inboxes.len(), 2);
+        assert_eq!(params.events, Some(vec!["intent_created".to_string(), "refund".to_string()]));
+
+        assert_eq!(
+            error(&format!("chain_id=8453&chain_id=10&inbox={}", INBOX)),
+            "invalid param chain_id: given more than once"
+        );
+        assert_eq!(
+            error(&format!(
+                "chain_id=8453&inbox={}&vault_creation_code=0x6080&vault_creation_code=0x6080",
+                INBOX
+            )),
+            "invalid param vault_creation_code: given more than once"
+        );
+    }
+}
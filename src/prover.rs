@@ -0,0 +1,153 @@
+use crate::abi::prover;
+use crate::params::Params;
+use crate::pb::intentsource::v1::{IntentAlreadyProven, IntentEvents, IntentProven, ProverEvents};
+use crate::{block_logs, decode_failure, decode_log, event_id, has_topic, hex_string, unknown_log, LogContext};
+use substreams::pb::substreams::Clock;
+use substreams::prelude::*;
+use substreams::store::{StoreGetInt64, StoreSetIfNotExistsInt64};
+use substreams_ethereum::pb::eth::v2 as eth;
+use substreams_entity_change::tables::Tables;
+
+// Prover contracts to watch: those listed with the `prover` param, from
+// their deployment block, and those referenced by indexed intents,
+// IntentCreated.prover on the source chain and Fulfillment.prover on the
+// destination chain. Keyed `prover:{address}` with the block they were
+// first seen at.
+#[substreams::handlers::store]
+fn store_provers(params: String, clock: Clock, events: IntentEvents, store: StoreSetIfNotExistsInt64) {
+    let params = Params::parse(&params).unwrap_or_else(|err| panic!("{}", err));
+    for prover in params.provers.iter().filter(|prover| clock.number >= prover.start_block) {
+        store.set_if_not_exists(0, prover_key(&hex_string(&prover.address)), &(clock.number as i64));
+    }
+    for event in &events.intent_created {
+        store.set_if_not_exists(event.ordinal, prover_key(&event.prover), &(event.block_number as i64));
+    }
+    for event in &events.fulfillment {
+        store.set_if_not_exists(event.ordinal, prover_key(&event.prover), &(event.block_number as i64));
+    }
+}
+
+#[substreams::handlers::map]
+fn map_prover_events(
+    params: String,
+    blk: eth::Block,
+    provers: StoreGetInt64,
+) -> Result<ProverEvents, substreams::errors::Error> {
+    let params = Params::parse(&params)?;
+    let mut events = ProverEvents::default();
+
+    for (trx, log) in block_logs(&blk, false) {
+        // Provers listed in params are watched for every event. Other
+        // provers are only looked up in store_provers for logs shaped like a
+        // prover event, to keep store reads off the rest of the block.
+        let listed = params.is_prover(&log.address, blk.number);
+        if !listed && !(is_prover_event(log) && provers.get_last(prover_key(&hex_string(&log.address))).is_some()) {
+            continue;
+        }
+
+        let ctx = LogContext::new(&blk, trx, params.chain_id);
+        match decode_prover_log(log, &ctx, &mut events) {
+            Ok(true) => {},
+            Ok(false) => events.unknown_logs.push(unknown_log(log, &ctx)),
+            Err(reason) => events.decode_failures.push(decode_failure(log, &ctx, reason)),
+        }
+    }
+
+    if !params.emits("intent_proven") {
+        events.intent_proven.clear();
+    }
+    if !params.emits("intent_already_proven") {
+        events.intent_already_proven.clear();
+    }
+
+    Ok(events)
+}
+
+fn prover_key(address: &str) -> String {
+    format!("prover:{}", address)
+}
+
+fn is_prover_event(log: &eth::Log) -> bool {
+    has_topic::<prover::events::IntentProven>(log) || has_topic::<prover::events::IntentAlreadyProven>(log)
+}
+
+// Returns Ok(false) when topic0 isn't one of the known prover events
+fn decode_prover_log(log: &eth::Log, ctx: &LogContext, events: &mut ProverEvents) -> Result<bool, String> {
+    if let Some(event) = decode_intent_proven_event(log, ctx)? {
+        events.intent_proven.push(event);
+    } else if let Some(event) = decode_intent_already_proven_event(log, ctx)? {
+        events.intent_already_proven.push(event);
+    } else {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+fn decode_intent_proven_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<IntentProven>, String> {
+    let Some(event) = decode_log::<prover::events::IntentProven>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(IntentProven {
+        intent_hash: hex_string(&event.hash),
+        claimant: hex_string(&event.claimant),
+        prover: hex_string(&log.address),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+fn decode_intent_already_proven_event(log: &eth::Log, ctx: &LogContext) -> Result<Option<IntentAlreadyProven>, String> {
+    let Some(event) = decode_log::<prover::events::IntentAlreadyProven>(log)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(IntentAlreadyProven {
+        intent_hash: hex_string(&event.intent_hash),
+        prover: hex_string(&log.address),
+        block_number: ctx.block_number,
+        tx_hash: ctx.tx_hash.clone(),
+        timestamp: ctx.timestamp,
+        log_index: log.index,
+        tx_index: ctx.tx_index,
+        ordinal: log.ordinal,
+        chain_id: ctx.chain_id,
+    }))
+}
+
+pub fn write_prover_events(tables: &mut Tables, events: &ProverEvents) {
+    for event in &events.intent_proven {
+        tables
+            .create_row("intent_proven", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("intent_hash", &event.intent_hash)
+            .set("claimant", &event.claimant)
+            .set("prover", &event.prover)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+
+    for event in &events.intent_already_proven {
+        tables
+            .create_row("intent_already_proven", &event_id(event.chain_id, event.block_number, &event.tx_hash, event.log_index))
+            .set("chain_id", event.chain_id)
+            .set("intent_hash", &event.intent_hash)
+            .set("prover", &event.prover)
+            .set("block_number", event.block_number)
+            .set("tx_hash", &event.tx_hash)
+            .set("timestamp", event.timestamp)
+            .set("log_index", u64::from(event.log_index))
+            .set("tx_index", u64::from(event.tx_index))
+            .set("ordinal", event.ordinal);
+    }
+}
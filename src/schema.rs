@@ -5,14 +5,34 @@ pub struct IntentCreatedDoc {
     #[serde(rename = "_id")]
     pub id: String,
     pub intent_hash: String,
+    pub route_hash: String,
+    pub reward_hash: String,
+    pub computed_intent_hash: String,
+    pub hash_mismatch: bool,
+    pub intent_source: String,
+    pub vault: String,
+    pub salt: String,
     pub creator: String,
-    pub source_chain_id: u64,
-    pub destination_chain_id: u64,
-    pub reward_token: String,
-    pub reward_amount: String,
+    pub prover: String,
+    pub source_chain_id: String,
+    pub destination_chain_id: String,
+    pub inbox_address: String,
+    pub route_tokens: Vec<String>,
+    pub route_amounts: Vec<String>,
+    pub call_targets: Vec<String>,
+    pub call_data: Vec<String>,
+    pub call_values: Vec<String>,
+    pub deadline: String,
+    pub native_value: String,
+    pub reward_tokens: Vec<String>,
+    pub reward_amounts: Vec<String>,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,11 +41,16 @@ pub struct IntentFundedDoc {
     pub id: String,
     pub intent_hash: String,
     pub funder: String,
-    pub reward_token: String,
-    pub reward_amount: String,
+    pub vault: String,
+    pub funded_tokens: Vec<String>,
+    pub funded_amounts: Vec<String>,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,37 +59,54 @@ pub struct IntentPartiallyFundedDoc {
     pub id: String,
     pub intent_hash: String,
     pub funder: String,
-    pub reward_token: String,
-    pub reward_amount: String,
+    pub vault: String,
+    pub funded_tokens: Vec<String>,
+    pub funded_amounts: Vec<String>,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WithdrawalDoc {
     #[serde(rename = "_id")]
     pub id: String,
-    pub intent_hash: String,
-    pub claimant: String,
-    pub reward_token: String,
-    pub reward_amount: String,
+    pub hash: String,
+    pub recipient: String,
+    pub paid_tokens: Vec<String>,
+    pub paid_amounts: Vec<String>,
+    pub vault: String,
+    pub vault_mismatch: bool,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RefundDoc {
     #[serde(rename = "_id")]
     pub id: String,
-    pub intent_hash: String,
+    pub hash: String,
     pub recipient: String,
-    pub reward_token: String,
-    pub reward_amount: String,
+    pub paid_tokens: Vec<String>,
+    pub paid_amounts: Vec<String>,
+    pub vault: String,
+    pub vault_mismatch: bool,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,19 +118,27 @@ pub struct IntentProofChallengedDoc {
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FulfillmentDoc {
     #[serde(rename = "_id")]
     pub id: String,
-    pub fulfillment_hash: String,
-    pub chain_id: u64,
-    pub fulfiller: String,
-    pub recipient: String,
+    pub hash: String,
+    pub source_chain_id: String,
+    pub prover: String,
+    pub claimant: String,
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -100,6 +150,365 @@ pub struct OrderFilledDoc {
     pub block_number: u64,
     pub tx_hash: String,
     pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToBeProvenDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub source_chain_id: String,
+    pub claimant: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HyperInstantFulfillmentDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub source_chain_id: String,
+    pub claimant: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddToBatchDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub source_chain_id: String,
+    pub claimant: String,
+    pub prover: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolverWhitelistChangedDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub solver: String,
+    pub can_solve: bool,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolvingIsPublicDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MailboxSetDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub mailbox: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntentProvenDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub claimant: String,
+    pub prover: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntentAlreadyProvenDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub prover: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub log_index: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodeFailureDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub contract: String,
+    pub topic0: String,
+    pub reason: String,
+    pub log_index: u64,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnknownEventDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub contract: String,
+    pub topic0: String,
+    pub topics: Vec<String>,
+    pub data: String,
+    pub log_index: u64,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub tx_index: u64,
+    pub ordinal: u64,
+    pub chain_id: u64,
+}
+
+// One row per intent, written by the chain it was created on and kept
+// current by db_out from store_intent_state and store_intent_status. Each
+// transition fills its `{status}_block`, `{status}_timestamp` and
+// `{status}_chain_id` fields; `status` is the furthest status reached.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntentDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub status: String,
+    pub created_block: Option<u64>,
+    pub created_timestamp: Option<u64>,
+    pub created_chain_id: Option<u64>,
+    pub partially_funded_block: Option<u64>,
+    pub partially_funded_timestamp: Option<u64>,
+    pub partially_funded_chain_id: Option<u64>,
+    pub funded_block: Option<u64>,
+    pub funded_timestamp: Option<u64>,
+    pub funded_chain_id: Option<u64>,
+    pub fulfilled_block: Option<u64>,
+    pub fulfilled_timestamp: Option<u64>,
+    pub fulfilled_chain_id: Option<u64>,
+    pub proven_block: Option<u64>,
+    pub proven_timestamp: Option<u64>,
+    pub proven_chain_id: Option<u64>,
+    pub challenged_block: Option<u64>,
+    pub challenged_timestamp: Option<u64>,
+    pub challenged_chain_id: Option<u64>,
+    pub withdrawn_block: Option<u64>,
+    pub withdrawn_timestamp: Option<u64>,
+    pub withdrawn_chain_id: Option<u64>,
+    pub refunded_block: Option<u64>,
+    pub refunded_timestamp: Option<u64>,
+    pub refunded_chain_id: Option<u64>,
+    // Set by map_expired_intents once the deadline passes unsettled
+    pub expired: Option<bool>,
+    pub deadline: Option<u64>,
+    pub expired_block: Option<u64>,
+    pub expired_timestamp: Option<u64>,
+    pub refund_eligible: Option<bool>,
+}
+
+// Intents created on the indexed chain: creation joined with withdrawal,
+// and with fulfillment for same-chain intents, by map_intent_latency
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntentLatencyDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub chain_id: u64,
+    pub created_timestamp: u64,
+    // Set from the fulfillment, absent for cross-chain intents
+    pub solver: Option<String>,
+    pub prover: Option<String>,
+    pub fulfilled_timestamp: Option<u64>,
+    pub fulfilled_seconds: Option<u64>,
+    pub fulfilled_tx_hash: Option<String>,
+    pub withdrawn_timestamp: Option<u64>,
+    pub withdrawn_seconds: Option<u64>,
+    pub withdrawn_tx_hash: Option<String>,
+}
+
+// Fill latency percentiles over the trailing `window_days`, one row per
+// solver (solver_latency) or prover (prover_latency) and chain. Percentiles
+// are bucket upper bounds, accurate to within 25%.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatencyPercentileDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub address: String,
+    pub window_days: u64,
+    pub window_fills: u64,
+    pub total_fills: u64,
+    pub p50_seconds: u64,
+    pub p90_seconds: u64,
+    pub p99_seconds: u64,
+    pub updated_block: u64,
+    pub updated_timestamp: u64,
+}
+
+// Solver leaderboard, one row per solver and chain. Reward totals are
+// stored as extra `reward_{token}` fields (decimal strings), `reward_native`
+// for native value.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolverDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub solver: String,
+    pub fulfillments: Option<u64>,
+    pub orders_filled: Option<u64>,
+    pub withdrawals: Option<u64>,
+    pub intents: Option<u64>,
+    pub first_block: u64,
+    pub last_block: u64,
+    #[serde(flatten)]
+    pub rewards: std::collections::BTreeMap<String, String>,
+}
+
+// Creator activity, one row per creator and chain. Reward value offered is
+// stored as extra `reward_{token}` fields (decimal strings), `reward_native`
+// for native value.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatorDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub creator: String,
+    pub created: Option<u64>,
+    pub funded: Option<u64>,
+    pub fulfilled: Option<u64>,
+    pub proven: Option<u64>,
+    pub refunded: Option<u64>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    #[serde(flatten)]
+    pub rewards: std::collections::BTreeMap<String, String>,
+}
+
+// token_volume_daily / token_volume_hourly: volume of created intents per
+// chain, period, kind (`reward` or `route`) and token. `volume` is a decimal
+// string in token units.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenVolumeDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub period_start: u64,
+    pub kind: String,
+    pub token: String,
+    pub volume: String,
+    pub intents: u64,
+}
+
+// One row per (source, destination) chain pair. Prover mix and reward
+// volume are stored as extra `prover_{address}` (intent count) and
+// `reward_{token}` (decimal string) fields, not modelled here.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorridorDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub source_chain_id: String,
+    pub destination_chain_id: String,
+    pub intents: u64,
+    pub fulfilled: Option<u64>,
+    pub proven: Option<u64>,
+    pub refunded: Option<u64>,
+}
+
+// Funded total per intent and token, keyed `{intent_hash}_{token}`.
+// `required` and `fully_funded` are only set for intents created on the
+// indexed chain.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntentFundingDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub intent_hash: String,
+    pub token: String,
+    pub funded: String,
+    pub required: Option<String>,
+    pub fully_funded: Option<bool>,
+}
+
+// Reward escrow per intent and token, keyed `{intent_hash}_{token}`:
+// funded amounts minus Withdrawal and Refund payouts. `negative` and
+// `residual` (settled with something left) flag rows to reconcile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EscrowBalanceDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub intent_hash: String,
+    pub token: String,
+    pub balance: String,
+    pub negative: bool,
+    pub settled: bool,
+    pub residual: bool,
+    pub updated_block: u64,
+    pub updated_timestamp: u64,
+}
+
+// Protocol TVL per chain, UTC day and token: value escrowed across all
+// intent vaults. `tvl` is the latest per-block sample (closing value once the
+// day is over); a day opens at the previous close, so unchanged days have a
+// row too.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TvlDailyDoc {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub chain_id: u64,
+    pub day_start: u64,
+    pub token: String,
+    pub tvl: String,
+    pub high: String,
+    pub low: String,
+    pub updated_block: u64,
+    pub updated_timestamp: u64,
 }
 
 // MongoDB Collection Names
@@ -111,58 +520,209 @@ pub const REFUND_COLLECTION: &str = "refund";
 pub const INTENT_PROOF_CHALLENGED_COLLECTION: &str = "intent_proof_challenged";
 pub const FULFILLMENT_COLLECTION: &str = "fulfillment";
 pub const ORDER_FILLED_COLLECTION: &str = "order_filled";
+pub const TO_BE_PROVEN_COLLECTION: &str = "to_be_proven";
+pub const HYPER_INSTANT_FULFILLMENT_COLLECTION: &str = "hyper_instant_fulfillment";
+pub const ADD_TO_BATCH_COLLECTION: &str = "add_to_batch";
+pub const SOLVER_WHITELIST_CHANGED_COLLECTION: &str = "solver_whitelist_changed";
+pub const SOLVING_IS_PUBLIC_COLLECTION: &str = "solving_is_public";
+pub const MAILBOX_SET_COLLECTION: &str = "mailbox_set";
+pub const INTENT_PROVEN_COLLECTION: &str = "intent_proven";
+pub const INTENT_ALREADY_PROVEN_COLLECTION: &str = "intent_already_proven";
+pub const DECODE_FAILURES_COLLECTION: &str = "decode_failures";
+pub const UNKNOWN_EVENTS_COLLECTION: &str = "unknown_events";
+pub const INTENTS_COLLECTION: &str = "intents";
+pub const INTENT_LATENCY_COLLECTION: &str = "intent_latency";
+pub const SOLVER_LATENCY_COLLECTION: &str = "solver_latency";
+pub const PROVER_LATENCY_COLLECTION: &str = "prover_latency";
+pub const SOLVERS_COLLECTION: &str = "solvers";
+pub const CREATORS_COLLECTION: &str = "creators";
+pub const TOKEN_VOLUME_DAILY_COLLECTION: &str = "token_volume_daily";
+pub const TOKEN_VOLUME_HOURLY_COLLECTION: &str = "token_volume_hourly";
+pub const CORRIDORS_COLLECTION: &str = "corridors";
+pub const INTENT_FUNDING_COLLECTION: &str = "intent_funding";
+pub const ESCROW_BALANCES_COLLECTION: &str = "escrow_balances";
+pub const TVL_DAILY_COLLECTION: &str = "tvl_daily";
 
 // Index specifications for MongoDB
 pub struct MongoIndexSpec {
     pub collection: &'static str,
-    pub field: &'static str,
+    pub fields: &'static [&'static str],
     pub unique: bool,
 }
 
+// db_out keys every event row by chain, block, tx hash and log index.
+// (chain_id, tx_hash, log_index) identifies the log, so that triple carries
+// the unique index.
+const EVENT_KEY: &[&str] = &["chain_id", "tx_hash", "log_index"];
+
 pub const MONGO_INDEXES: &[MongoIndexSpec] = &[
     // IntentCreated indexes
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "intent_hash", unique: true },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "creator", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "prover", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "source_chain_id", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "destination_chain_id", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "reward_token", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "block_number", unique: false },
-    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, field: "timestamp", unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["route_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["hash_mismatch"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["vault"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["chain_id"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["creator"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["destination_chain_id"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["reward_tokens"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["route_tokens"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["block_number"], unique: false },
+    MongoIndexSpec { collection: INTENT_CREATED_COLLECTION, fields: &["timestamp"], unique: false },
     
     // IntentFunded indexes
-    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, field: "intent_hash", unique: false },
-    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, field: "funder", unique: false },
-    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, fields: &["funder"], unique: false },
+    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, fields: &["vault"], unique: false },
+    MongoIndexSpec { collection: INTENT_FUNDED_COLLECTION, fields: &["block_number"], unique: false },
     
     // IntentPartiallyFunded indexes
-    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, field: "intent_hash", unique: false },
-    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, field: "funder", unique: false },
-    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, fields: &["funder"], unique: false },
+    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, fields: &["vault"], unique: false },
+    MongoIndexSpec { collection: INTENT_PARTIALLY_FUNDED_COLLECTION, fields: &["block_number"], unique: false },
     
     // Withdrawal indexes
-    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, field: "hash", unique: false },
-    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, field: "recipient", unique: false },
-    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, fields: &["hash"], unique: false },
+    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, fields: &["recipient"], unique: false },
+    MongoIndexSpec { collection: WITHDRAWAL_COLLECTION, fields: &["block_number"], unique: false },
     
     // Refund indexes
-    MongoIndexSpec { collection: REFUND_COLLECTION, field: "hash", unique: false },
-    MongoIndexSpec { collection: REFUND_COLLECTION, field: "recipient", unique: false },
-    MongoIndexSpec { collection: REFUND_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: REFUND_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: REFUND_COLLECTION, fields: &["hash"], unique: false },
+    MongoIndexSpec { collection: REFUND_COLLECTION, fields: &["recipient"], unique: false },
+    MongoIndexSpec { collection: REFUND_COLLECTION, fields: &["block_number"], unique: false },
     
     // IntentProofChallenged indexes
-    MongoIndexSpec { collection: INTENT_PROOF_CHALLENGED_COLLECTION, field: "intent_hash", unique: false },
-    MongoIndexSpec { collection: INTENT_PROOF_CHALLENGED_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: INTENT_PROOF_CHALLENGED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_PROOF_CHALLENGED_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_PROOF_CHALLENGED_COLLECTION, fields: &["block_number"], unique: false },
     
     // Fulfillment indexes
-    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, field: "hash", unique: false },
-    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, field: "source_chain_id", unique: false },
-    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, field: "prover", unique: false },
-    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, field: "claimant", unique: false },
-    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: &["hash"], unique: false },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: &["claimant"], unique: false },
+    MongoIndexSpec { collection: FULFILLMENT_COLLECTION, fields: &["block_number"], unique: false },
     
     // OrderFilled indexes
-    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, field: "order_id", unique: true },
-    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, field: "solver", unique: false },
-    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, field: "block_number", unique: false },
+    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, fields: &["order_id"], unique: false },
+    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, fields: &["solver"], unique: false },
+    MongoIndexSpec { collection: ORDER_FILLED_COLLECTION, fields: &["block_number"], unique: false },
+
+    // ToBeProven indexes
+    MongoIndexSpec { collection: TO_BE_PROVEN_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: TO_BE_PROVEN_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: TO_BE_PROVEN_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: TO_BE_PROVEN_COLLECTION, fields: &["claimant"], unique: false },
+    MongoIndexSpec { collection: TO_BE_PROVEN_COLLECTION, fields: &["block_number"], unique: false },
+
+    // HyperInstantFulfillment indexes
+    MongoIndexSpec { collection: HYPER_INSTANT_FULFILLMENT_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: HYPER_INSTANT_FULFILLMENT_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: HYPER_INSTANT_FULFILLMENT_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: HYPER_INSTANT_FULFILLMENT_COLLECTION, fields: &["claimant"], unique: false },
+    MongoIndexSpec { collection: HYPER_INSTANT_FULFILLMENT_COLLECTION, fields: &["block_number"], unique: false },
+
+    // AddToBatch indexes
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: &["claimant"], unique: false },
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: ADD_TO_BATCH_COLLECTION, fields: &["block_number"], unique: false },
+
+    // SolverWhitelistChanged indexes
+    MongoIndexSpec { collection: SOLVER_WHITELIST_CHANGED_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: SOLVER_WHITELIST_CHANGED_COLLECTION, fields: &["solver"], unique: false },
+    MongoIndexSpec { collection: SOLVER_WHITELIST_CHANGED_COLLECTION, fields: &["can_solve"], unique: false },
+    MongoIndexSpec { collection: SOLVER_WHITELIST_CHANGED_COLLECTION, fields: &["block_number"], unique: false },
+
+    // SolvingIsPublic indexes
+    MongoIndexSpec { collection: SOLVING_IS_PUBLIC_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: SOLVING_IS_PUBLIC_COLLECTION, fields: &["block_number"], unique: false },
+
+    // MailboxSet indexes
+    MongoIndexSpec { collection: MAILBOX_SET_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: MAILBOX_SET_COLLECTION, fields: &["mailbox"], unique: false },
+    MongoIndexSpec { collection: MAILBOX_SET_COLLECTION, fields: &["block_number"], unique: false },
+
+    // IntentProven indexes
+    MongoIndexSpec { collection: INTENT_PROVEN_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_PROVEN_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_PROVEN_COLLECTION, fields: &["claimant"], unique: false },
+    MongoIndexSpec { collection: INTENT_PROVEN_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: INTENT_PROVEN_COLLECTION, fields: &["block_number"], unique: false },
+
+    // IntentAlreadyProven indexes
+    MongoIndexSpec { collection: INTENT_ALREADY_PROVEN_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: INTENT_ALREADY_PROVEN_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_ALREADY_PROVEN_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: INTENT_ALREADY_PROVEN_COLLECTION, fields: &["block_number"], unique: false },
+
+    // DecodeFailure indexes
+    MongoIndexSpec { collection: DECODE_FAILURES_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: DECODE_FAILURES_COLLECTION, fields: &["contract"], unique: false },
+    MongoIndexSpec { collection: DECODE_FAILURES_COLLECTION, fields: &["topic0"], unique: false },
+    MongoIndexSpec { collection: DECODE_FAILURES_COLLECTION, fields: &["block_number"], unique: false },
+
+    // UnknownEvent indexes
+    MongoIndexSpec { collection: UNKNOWN_EVENTS_COLLECTION, fields: EVENT_KEY, unique: true },
+    MongoIndexSpec { collection: UNKNOWN_EVENTS_COLLECTION, fields: &["contract"], unique: false },
+    MongoIndexSpec { collection: UNKNOWN_EVENTS_COLLECTION, fields: &["topic0"], unique: false },
+    MongoIndexSpec { collection: UNKNOWN_EVENTS_COLLECTION, fields: &["block_number"], unique: false },
+
+    // Intent indexes (keyed by intent hash)
+    MongoIndexSpec { collection: INTENTS_COLLECTION, fields: &["status"], unique: false },
+    MongoIndexSpec { collection: INTENTS_COLLECTION, fields: &["created_timestamp"], unique: false },
+    MongoIndexSpec { collection: INTENTS_COLLECTION, fields: &["refund_eligible"], unique: false },
+
+    // Latency indexes (keyed by intent hash, or chain and address)
+    MongoIndexSpec { collection: INTENT_LATENCY_COLLECTION, fields: &["solver"], unique: false },
+    MongoIndexSpec { collection: INTENT_LATENCY_COLLECTION, fields: &["prover"], unique: false },
+    MongoIndexSpec { collection: INTENT_LATENCY_COLLECTION, fields: &["fulfilled_seconds"], unique: false },
+    MongoIndexSpec { collection: SOLVER_LATENCY_COLLECTION, fields: &["p50_seconds"], unique: false },
+    MongoIndexSpec { collection: PROVER_LATENCY_COLLECTION, fields: &["p50_seconds"], unique: false },
+
+    // Solver indexes (keyed by chain and address)
+    MongoIndexSpec { collection: SOLVERS_COLLECTION, fields: &["solver"], unique: false },
+    MongoIndexSpec { collection: SOLVERS_COLLECTION, fields: &["fulfillments"], unique: false },
+    MongoIndexSpec { collection: SOLVERS_COLLECTION, fields: &["intents"], unique: false },
+    MongoIndexSpec { collection: SOLVERS_COLLECTION, fields: &["last_block"], unique: false },
+
+    // Creator indexes (keyed by chain and address)
+    MongoIndexSpec { collection: CREATORS_COLLECTION, fields: &["creator"], unique: false },
+    MongoIndexSpec { collection: CREATORS_COLLECTION, fields: &["created"], unique: false },
+    MongoIndexSpec { collection: CREATORS_COLLECTION, fields: &["last_seen"], unique: false },
+
+    // Token volume indexes (keyed by chain, period, kind and token)
+    MongoIndexSpec { collection: TOKEN_VOLUME_DAILY_COLLECTION, fields: &["period_start", "token"], unique: false },
+    MongoIndexSpec { collection: TOKEN_VOLUME_DAILY_COLLECTION, fields: &["token"], unique: false },
+    MongoIndexSpec { collection: TOKEN_VOLUME_HOURLY_COLLECTION, fields: &["period_start", "token"], unique: false },
+    MongoIndexSpec { collection: TOKEN_VOLUME_HOURLY_COLLECTION, fields: &["token"], unique: false },
+
+    // Corridor indexes (keyed by source and destination chain)
+    MongoIndexSpec { collection: CORRIDORS_COLLECTION, fields: &["source_chain_id"], unique: false },
+    MongoIndexSpec { collection: CORRIDORS_COLLECTION, fields: &["destination_chain_id"], unique: false },
+    MongoIndexSpec { collection: CORRIDORS_COLLECTION, fields: &["intents"], unique: false },
+
+    // Intent funding indexes (keyed by intent hash and token)
+    MongoIndexSpec { collection: INTENT_FUNDING_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: INTENT_FUNDING_COLLECTION, fields: &["fully_funded"], unique: false },
+
+    // Escrow balance indexes (keyed by intent hash and token)
+    MongoIndexSpec { collection: ESCROW_BALANCES_COLLECTION, fields: &["intent_hash"], unique: false },
+    MongoIndexSpec { collection: ESCROW_BALANCES_COLLECTION, fields: &["negative"], unique: false },
+    MongoIndexSpec { collection: ESCROW_BALANCES_COLLECTION, fields: &["residual"], unique: false },
+
+    // TVL indexes (keyed by chain, day and token)
+    MongoIndexSpec { collection: TVL_DAILY_COLLECTION, fields: &["day_start", "token"], unique: false },
+    MongoIndexSpec { collection: TVL_DAILY_COLLECTION, fields: &["token"], unique: false },
 ];
\ No newline at end of file
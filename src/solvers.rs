@@ -0,0 +1,156 @@
+use crate::big_int;
+use crate::lifecycle::intent_key;
+use crate::pb::intentsource::v1::{IntentCreated, IntentEvents};
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    DeltaBigInt, DeltaInt64, Deltas, StoreAddBigInt, StoreAddInt64, StoreGetProto, StoreMaxInt64, StoreMinInt64,
+    StoreSetIfNotExistsInt64,
+};
+use substreams_entity_change::tables::Tables;
+
+// Solvers are identified by the address they fill as: the Fulfillment
+// claimant, the OrderFilled solver and, on the source chain, the recipient
+// of the reward Withdrawal.
+
+// One key per (solver, intent), `solver:{chain_id}:{address}:intent:{intent_hash}`.
+// Only the first sighting creates the key, which is what store_solver_stats
+// counts as a distinct intent.
+#[substreams::handlers::store]
+fn store_solver_intents(events: IntentEvents, store: StoreSetIfNotExistsInt64) {
+    for event in &events.fulfillment {
+        store.set_if_not_exists(event.ordinal, solver_intent_key(event.chain_id, &event.claimant, &event.hash), &(event.block_number as i64));
+    }
+    for event in &events.withdrawal {
+        store.set_if_not_exists(event.ordinal, solver_intent_key(event.chain_id, &event.recipient, &event.hash), &(event.block_number as i64));
+    }
+}
+
+// Running counters per solver, `solver:{chain_id}:{address}:{counter}`
+#[substreams::handlers::store]
+fn store_solver_stats(events: IntentEvents, solver_intents: Deltas<DeltaInt64>, store: StoreAddInt64) {
+    for event in &events.fulfillment {
+        store.add(event.ordinal, counter_key(event.chain_id, &event.claimant, "fulfillments"), 1);
+    }
+    for event in &events.order_filled {
+        store.add(event.ordinal, counter_key(event.chain_id, &event.solver, "orders_filled"), 1);
+    }
+    for event in &events.withdrawal {
+        store.add(event.ordinal, counter_key(event.chain_id, &event.recipient, "withdrawals"), 1);
+    }
+    for delta in solver_intents.deltas.iter() {
+        if delta.operation == Operation::Create {
+            let chain_id = key::segment_at(&delta.key, 1);
+            let solver = key::segment_at(&delta.key, 2);
+            store.add(delta.ordinal, format!("solver:{}:{}:intents", chain_id, solver), 1);
+        }
+    }
+}
+
+// Rewards withdrawn by each solver, `solver:{chain_id}:{address}:reward:{token}`. The
+// intent's reward tokens come from its IntentCreated, so only intents
+// published on the indexed chain are counted; native value is kept under the
+// `native` token.
+#[substreams::handlers::store]
+fn store_solver_rewards(events: IntentEvents, intents: StoreGetProto<IntentCreated>, store: StoreAddBigInt) {
+    for event in &events.withdrawal {
+        let Some(intent) = intents.get_last(intent_key(&event.hash)) else {
+            continue;
+        };
+
+        for reward in &intent.reward_tokens {
+            store.add(event.ordinal, reward_key(event.chain_id, &event.recipient, &reward.token), big_int(&reward.amount));
+        }
+        let native_value = big_int(&intent.native_value);
+        if native_value > BigInt::zero() {
+            store.add(event.ordinal, reward_key(event.chain_id, &event.recipient, "native"), native_value);
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_solver_first_block(events: IntentEvents, store: StoreMinInt64) {
+    for (ordinal, chain_id, solver, block_number) in activity(&events) {
+        store.min(ordinal, solver_key(chain_id, solver), block_number as i64);
+    }
+}
+
+#[substreams::handlers::store]
+fn store_solver_last_block(events: IntentEvents, store: StoreMaxInt64) {
+    for (ordinal, chain_id, solver, block_number) in activity(&events) {
+        store.max(ordinal, solver_key(chain_id, solver), block_number as i64);
+    }
+}
+
+// (ordinal, chain, solver, block) for every event a solver took part in
+fn activity(events: &IntentEvents) -> impl Iterator<Item = (u64, u64, &str, u64)> {
+    let fulfillments = events.fulfillment.iter().map(|e| (e.ordinal, e.chain_id, e.claimant.as_str(), e.block_number));
+    let orders = events.order_filled.iter().map(|e| (e.ordinal, e.chain_id, e.solver.as_str(), e.block_number));
+    let withdrawals = events.withdrawal.iter().map(|e| (e.ordinal, e.chain_id, e.recipient.as_str(), e.block_number));
+    fulfillments.chain(orders).chain(withdrawals)
+}
+
+fn solver_key(chain_id: u64, solver: &str) -> String {
+    format!("solver:{}:{}", chain_id, solver)
+}
+
+fn solver_intent_key(chain_id: u64, solver: &str, intent_hash: &str) -> String {
+    format!("solver:{}:{}:intent:{}", chain_id, solver, intent_hash)
+}
+
+fn counter_key(chain_id: u64, solver: &str, counter: &str) -> String {
+    format!("solver:{}:{}:{}", chain_id, solver, counter)
+}
+
+fn reward_key(chain_id: u64, solver: &str, token: &str) -> String {
+    format!("solver:{}:{}:reward:{}", chain_id, solver, token)
+}
+
+// `{chain_id}_{address}` row id from any `solver:{chain_id}:{address}...` key
+fn solver_id(key: &str) -> String {
+    format!("{}_{}", key::segment_at(key, 1), key::segment_at(key, 2))
+}
+
+// One `solvers` row per solver and chain, created with its first active
+// block. Counters and reward totals are written from the stores' new values,
+// so every row always holds the running totals: `fulfillments`,
+// `orders_filled`, `withdrawals`, `intents` (distinct), `reward_{token}`,
+// `first_block` and `last_block`.
+pub fn write_solvers(
+    tables: &mut Tables,
+    first_block: &Deltas<DeltaInt64>,
+    last_block: &Deltas<DeltaInt64>,
+    stats: &Deltas<DeltaInt64>,
+    rewards: &Deltas<DeltaBigInt>,
+) {
+    for delta in first_block.deltas.iter() {
+        let row = if delta.operation == Operation::Create {
+            let chain_id: u64 = key::segment_at(&delta.key, 1).parse().unwrap_or_default();
+            tables
+                .create_row("solvers", solver_id(&delta.key))
+                .set("chain_id", chain_id)
+                .set("solver", key::segment_at(&delta.key, 2))
+        } else {
+            tables.update_row("solvers", solver_id(&delta.key))
+        };
+        row.set("first_block", delta.new_value as u64);
+    }
+
+    for delta in last_block.deltas.iter() {
+        tables.update_row("solvers", solver_id(&delta.key)).set("last_block", delta.new_value as u64);
+    }
+
+    for delta in stats.deltas.iter() {
+        let counter = key::last_segment(&delta.key);
+        tables.update_row("solvers", solver_id(&delta.key)).set(counter, delta.new_value as u64);
+    }
+
+    for delta in rewards.deltas.iter() {
+        let token = key::last_segment(&delta.key);
+        tables
+            .update_row("solvers", solver_id(&delta.key))
+            .set(&format!("reward_{}", token), delta.new_value.clone());
+    }
+}
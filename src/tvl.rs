@@ -0,0 +1,180 @@
+use crate::big_int;
+use crate::escrow::ledger_entries;
+use crate::params::Params;
+use crate::pb::intentsource::v1::{IntentEvents, TvlSample, TvlSamples};
+use std::collections::BTreeSet;
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::pb::substreams::Clock;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    DeltaBigInt, DeltaInt64, Deltas, StoreAddBigInt, StoreAppend, StoreGetArray, StoreGetBigInt, StoreMaxBigInt,
+    StoreMinBigInt,
+};
+use substreams_entity_change::tables::Tables;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Value escrowed across all intent vaults per token, `tvl:{chain_id}:{token}`,
+// from the same funding and payout entries as the escrow ledger
+#[substreams::handlers::store]
+fn store_tvl(events: IntentEvents, store: StoreAddBigInt) {
+    for (ordinal, chain_id, _, entry) in ledger_entries(&events) {
+        store.add(ordinal, tvl_key(chain_id, &entry.token), entry.signed);
+    }
+}
+
+// Every `tvl:{chain_id}:{token}` key store_tvl has, appended under `keys`
+// when it is created, so a new day can be opened for all of them
+#[substreams::handlers::store]
+fn store_tvl_keys(tvl: Deltas<DeltaBigInt>, store: StoreAppend<String>) {
+    for delta in tvl.deltas.iter().filter(|delta| delta.operation == Operation::Create) {
+        store.append(delta.ordinal, "keys", delta.key.clone());
+    }
+}
+
+// TVL per token at the end of every block that changed it. The first block
+// of a UTC day also samples every other token at its previous close, so each
+// day has a row for every token, changed or not.
+//
+// Without `vault_creation_code` no funding or payout is attributed to a vault
+// and the ledger is empty, so nothing is sampled rather than reporting a TVL
+// of zero.
+#[substreams::handlers::map]
+fn map_tvl(
+    params: String,
+    clock: Clock,
+    events: IntentEvents,
+    block_timestamp: Deltas<DeltaInt64>,
+    tvl_keys: StoreGetArray<String>,
+    tvl: StoreGetBigInt,
+) -> Result<TvlSamples, substreams::errors::Error> {
+    let params = Params::parse(&params)?;
+    let mut samples = TvlSamples::default();
+    if params.vault_creation_code.is_none() {
+        return Ok(samples);
+    }
+
+    let timestamp = clock.timestamp.as_ref().map(|t| t.seconds as u64).unwrap_or_default();
+    let mut sample = |key: &str, tvl: BigInt| {
+        samples.samples.push(TvlSample {
+            chain_id: key::segment_at(key, 1).parse().unwrap_or_default(),
+            token: key::segment_at(key, 2).to_string(),
+            tvl: tvl.to_string(),
+            block_number: clock.number,
+            timestamp,
+        });
+    };
+
+    // Ordinal 0 is before any of the block's entries, so keys first added in
+    // this block have no previous close
+    let previous = block_timestamp.deltas.first().map(|delta| delta.old_value).unwrap_or_default();
+    if opens_day(previous, timestamp) {
+        for key in tvl_keys.get_last("keys").unwrap_or_default() {
+            if let Some(close) = tvl.get_at(0, &key) {
+                sample(&key, close);
+            }
+        }
+    }
+
+    // Every TVL change comes from a ledger entry of this block
+    let changed: BTreeSet<String> =
+        ledger_entries(&events).into_iter().map(|(_, chain_id, _, entry)| tvl_key(chain_id, &entry.token)).collect();
+    for key in changed {
+        if let Some(value) = tvl.get_last(&key) {
+            sample(&key, value);
+        }
+    }
+
+    Ok(samples)
+}
+
+// Whether a block at `timestamp` is the first of its UTC day, given the
+// previous block's timestamp (0 before the first block, which has nothing to
+// carry over)
+fn opens_day(previous: i64, timestamp: u64) -> bool {
+    previous > 0 && day_start(previous as u64) != day_start(timestamp)
+}
+
+fn tvl_key(chain_id: u64, token: &str) -> String {
+    format!("tvl:{}:{}", chain_id, token)
+}
+
+// Daily high and low of the per-block samples, `tvl:{chain_id}:{day_start}:{token}`
+#[substreams::handlers::store]
+fn store_tvl_daily_high(samples: TvlSamples, store: StoreMaxBigInt) {
+    for sample in &samples.samples {
+        store.max(0, daily_key(sample), big_int(&sample.tvl));
+    }
+}
+
+#[substreams::handlers::store]
+fn store_tvl_daily_low(samples: TvlSamples, store: StoreMinBigInt) {
+    for sample in &samples.samples {
+        store.min(0, daily_key(sample), big_int(&sample.tvl));
+    }
+}
+
+fn day_start(timestamp: u64) -> u64 {
+    timestamp / SECONDS_PER_DAY * SECONDS_PER_DAY
+}
+
+fn daily_key(sample: &TvlSample) -> String {
+    format!("tvl:{}:{}:{}", sample.chain_id, day_start(sample.timestamp), sample.token)
+}
+
+// `{chain_id}_{day_start}_{token}` from a `tvl:{chain_id}:{day_start}:{token}` key
+fn daily_id(key: &str) -> String {
+    format!("{}_{}_{}", key::segment_at(key, 1), key::segment_at(key, 2), key::segment_at(key, 3))
+}
+
+// One `tvl_daily` row per chain, UTC day and token, created with the day's
+// first sample. A day opens with the previous close of every token, so `tvl`
+// (the latest sample, the closing value once the day is over), `high` and
+// `low` cover unchanged days too.
+pub fn write_tvl_daily(tables: &mut Tables, samples: &TvlSamples, high: &Deltas<DeltaBigInt>, low: &Deltas<DeltaBigInt>) {
+    for delta in high.deltas.iter() {
+        let row = if delta.operation == Operation::Create {
+            let chain_id: u64 = key::segment_at(&delta.key, 1).parse().unwrap_or_default();
+            let day_start: u64 = key::segment_at(&delta.key, 2).parse().unwrap_or_default();
+            tables
+                .create_row("tvl_daily", daily_id(&delta.key))
+                .set("chain_id", chain_id)
+                .set("day_start", day_start)
+                .set("token", key::segment_at(&delta.key, 3))
+        } else {
+            tables.update_row("tvl_daily", daily_id(&delta.key))
+        };
+        row.set("high", delta.new_value.clone());
+    }
+
+    for delta in low.deltas.iter() {
+        tables.update_row("tvl_daily", daily_id(&delta.key)).set("low", delta.new_value.clone());
+    }
+
+    for sample in &samples.samples {
+        tables
+            .update_row("tvl_daily", daily_id(&daily_key(sample)))
+            .set("tvl", big_int(&sample.tvl))
+            .set("updated_block", sample.block_number)
+            .set("updated_timestamp", sample.timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2023-11-15 00:00 UTC
+    const DAY: u64 = 1_700_006_400;
+
+    #[test]
+    fn opens_a_day_on_its_first_block() {
+        assert!(opens_day((DAY - 2) as i64, DAY));
+        assert!(!opens_day(DAY as i64, DAY + 2));
+        assert!(!opens_day((DAY + SECONDS_PER_DAY - 2) as i64, DAY + SECONDS_PER_DAY - 1));
+        assert!(opens_day((DAY - 3 * SECONDS_PER_DAY) as i64, DAY));
+        assert!(!opens_day(0, DAY));
+    }
+}
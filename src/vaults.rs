@@ -0,0 +1,76 @@
+use crate::hex_string;
+use crate::pb::intentsource::v1::IntentEvents;
+use substreams::prelude::*;
+use substreams::store::{StoreGetString, StoreSetIfNotExistsString};
+use substreams_ethereum::pb::eth::v2 as eth;
+
+// Vault of every intent created on the indexed chain, both ways:
+// `vault:{vault_address}` -> intent hash, and `intent:{intent_hash}` ->
+// vault address, which map_intent_amounts matches transfers against. Empty
+// without vault_creation_code in the params.
+#[substreams::handlers::store]
+fn store_intent_vaults(events: IntentEvents, store: StoreSetIfNotExistsString) {
+    for event in &events.intent_created {
+        if event.vault.is_empty() {
+            continue;
+        }
+        store.set_if_not_exists(event.ordinal, vault_key(&event.vault), &event.intent_hash);
+        store.set_if_not_exists(event.ordinal, intent_vault_key(&event.intent_hash), &event.vault);
+    }
+}
+
+pub fn vault_key(vault: &str) -> String {
+    format!("vault:{}", vault)
+}
+
+pub fn intent_vault_key(intent_hash: &str) -> String {
+    format!("intent:{}", intent_hash)
+}
+
+// Checks the derived vaults against the chain. Settling an intent deploys
+// its vault with CREATE2 from the IntentSource, so a Withdrawal or Refund
+// whose transaction deployed contracts from the IntentSource, none of them at
+// the derived address, means the derivation doesn't match the deployment.
+pub fn check_settled_vaults(blk: &eth::Block, events: &mut IntentEvents, vaults: &StoreGetString) {
+    let settlements = events
+        .withdrawal
+        .iter_mut()
+        .map(|e| (&e.hash, e.tx_index, e.ordinal, &mut e.vault, &mut e.vault_mismatch))
+        .chain(
+            events
+                .refund
+                .iter_mut()
+                .map(|e| (&e.hash, e.tx_index, e.ordinal, &mut e.vault, &mut e.vault_mismatch)),
+        );
+
+    for (intent_hash, tx_index, ordinal, vault, vault_mismatch) in settlements {
+        let Some(derived) = vaults.get_last(intent_vault_key(intent_hash)) else {
+            continue;
+        };
+        if let Some(trx) = blk.transaction_traces.iter().find(|trx| trx.index == tx_index) {
+            let deployed = deployed_by_emitter(trx, ordinal);
+            *vault_mismatch = !deployed.is_empty() && !deployed.contains(&derived);
+        }
+        *vault = derived;
+    }
+}
+
+// Contracts created in the transaction by the contract that emitted the log
+// at `ordinal`
+fn deployed_by_emitter(trx: &eth::TransactionTrace, ordinal: u64) -> Vec<String> {
+    let Some(emitter) = trx
+        .calls
+        .iter()
+        .flat_map(|call| call.logs.iter())
+        .find(|log| log.ordinal == ordinal)
+        .map(|log| &log.address)
+    else {
+        return Vec::new();
+    };
+
+    trx.calls
+        .iter()
+        .filter(|call| call.call_type == eth::CallType::Create as i32 && !call.state_reverted && &call.caller == emitter)
+        .map(|call| hex_string(&call.address))
+        .collect()
+}
@@ -0,0 +1,108 @@
+use crate::big_int;
+use crate::pb::intentsource::v1::{IntentEvents, TokenAmount};
+use substreams::key;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::prelude::*;
+use substreams::scalar::BigInt;
+use substreams::store::{DeltaBigInt, DeltaInt64, Deltas, StoreAddBigInt, StoreAddInt64};
+use substreams_entity_change::tables::Tables;
+
+const PERIODS: &[(&str, u64)] = &[("day", 86_400), ("hour", 3_600)];
+
+// Token volume of created intents per period, keyed
+// `volume:{chain_id}:{day|hour}:{period_start}:{reward|route}:{token}`.
+// Additions commute, so segments backfilled in parallel merge to the same
+// totals as a linear run.
+#[substreams::handlers::store]
+fn store_token_volume(events: IntentEvents, store: StoreAddBigInt) {
+    for event in &events.intent_created {
+        for (kind, token, amount) in amounts(&event.reward_tokens, &event.route_tokens, &event.native_value) {
+            for (period, seconds) in PERIODS {
+                let period_start = event.timestamp / seconds * seconds;
+                store.add(event.ordinal, volume_key(event.chain_id, period, period_start, kind, token), amount.clone());
+            }
+        }
+    }
+}
+
+// Intents per period and token under the same keys as store_token_volume
+#[substreams::handlers::store]
+fn store_token_intents(events: IntentEvents, store: StoreAddInt64) {
+    for event in &events.intent_created {
+        for (kind, token, _) in amounts(&event.reward_tokens, &event.route_tokens, &event.native_value) {
+            for (period, seconds) in PERIODS {
+                let period_start = event.timestamp / seconds * seconds;
+                store.add(event.ordinal, volume_key(event.chain_id, period, period_start, kind, token), 1);
+            }
+        }
+    }
+}
+
+// (reward|route, token, amount) for every token an intent moves. Native
+// reward value counts as the `native` token.
+fn amounts<'a>(
+    reward_tokens: &'a [TokenAmount],
+    route_tokens: &'a [TokenAmount],
+    native_value: &str,
+) -> Vec<(&'static str, &'a str, BigInt)> {
+    let mut amounts: Vec<_> = reward_tokens
+        .iter()
+        .map(|t| ("reward", t.token.as_str(), big_int(&t.amount)))
+        .chain(route_tokens.iter().map(|t| ("route", t.token.as_str(), big_int(&t.amount))))
+        .collect();
+
+    let native_value = big_int(native_value);
+    if native_value > BigInt::zero() {
+        amounts.push(("reward", "native", native_value));
+    }
+    amounts
+}
+
+fn volume_key(chain_id: u64, period: &str, period_start: u64, kind: &str, token: &str) -> String {
+    format!("volume:{}:{}:{}:{}:{}", chain_id, period, period_start, kind, token)
+}
+
+fn table(period: &str) -> &'static str {
+    match period {
+        "hour" => "token_volume_hourly",
+        _ => "token_volume_daily",
+    }
+}
+
+// `{chain_id}_{period_start}_{reward|route}_{token}`
+fn row_id(key: &str) -> String {
+    format!(
+        "{}_{}_{}_{}",
+        key::segment_at(key, 1),
+        key::segment_at(key, 3),
+        key::segment_at(key, 4),
+        key::segment_at(key, 5)
+    )
+}
+
+// token_volume_daily and token_volume_hourly rows, one per chain, period,
+// kind and token. `volume` and `intents` are the stores' new values, never
+// increments, so replaying a block or a reorg leaves the rows correct.
+pub fn write_token_volume(tables: &mut Tables, volume: &Deltas<DeltaBigInt>, intents: &Deltas<DeltaInt64>) {
+    for delta in volume.deltas.iter() {
+        let table = table(key::segment_at(&delta.key, 2));
+        let row = if delta.operation == Operation::Create {
+            let chain_id: u64 = key::segment_at(&delta.key, 1).parse().unwrap_or_default();
+            let period_start: u64 = key::segment_at(&delta.key, 3).parse().unwrap_or_default();
+            tables
+                .create_row(table, row_id(&delta.key))
+                .set("chain_id", chain_id)
+                .set("period_start", period_start)
+                .set("kind", key::segment_at(&delta.key, 4))
+                .set("token", key::segment_at(&delta.key, 5))
+        } else {
+            tables.update_row(table, row_id(&delta.key))
+        };
+        row.set("volume", delta.new_value.clone());
+    }
+
+    for delta in intents.deltas.iter() {
+        let table = table(key::segment_at(&delta.key, 2));
+        tables.update_row(table, row_id(&delta.key)).set("intents", delta.new_value as u64);
+    }
+}